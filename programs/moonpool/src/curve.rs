@@ -0,0 +1,678 @@
+use crate::errors::ErrorCode;
+use crate::math::{self, Rounding, K_PRECISION};
+use crate::state::{BASE_DROPLET_PRICE, DROPLET_MINT_DECIMALS, K, MAX_BASE_DROPLET_PRICE, MAX_K};
+use anchor_lang::prelude::*;
+
+/// Prices a pool's droplets against its supply. Amounts and supplies are in droplet base
+/// units, costs in quote base units. Buys round up and sells round down.
+pub trait BondingCurve {
+    fn buy_cost(&self, supply: u64, amount: u64) -> Result<u64>;
+    fn sell_proceeds(&self, supply: u64, amount: u64) -> Result<u64>;
+    /// Marginal price of a whole droplet at `supply`, rounded down.
+    fn spot_price(&self, supply: u64) -> Result<u64>;
+    /// Largest amount, possibly 0, that buys at `supply` for at most `cost`.
+    fn buy_amount(&self, supply: u64, cost: u64) -> Result<u64>;
+    /// Smallest amount whose sale at `supply` takes at least `proceeds`.
+    fn sell_amount(&self, supply: u64, proceeds: u64) -> Result<u64>;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum CurveKind {
+    // Price per droplet base unit of k * base_price * supply, k at K_PRECISION
+    Linear {
+        k: u64,
+        base_price: u64,
+    },
+    // Starts at initial_price per whole droplet and compounds by growth_rate (at
+    // K_PRECISION) every step droplet base units
+    Exponential {
+        initial_price: u64,
+        growth_rate: u64,
+        step: u64,
+    },
+    // Trades against virtual reserves holding quote_reserve * droplet_reserve constant
+    ConstantProduct {
+        virtual_quote_reserve: u64,
+        virtual_droplet_reserve: u64,
+    },
+    // Linear until the whole droplet price reaches max_price, flat after
+    Capped {
+        k: u64,
+        base_price: u64,
+        max_price: u64,
+    },
+}
+
+impl Default for CurveKind {
+    fn default() -> Self {
+        CurveKind::Linear {
+            k: K,
+            base_price: BASE_DROPLET_PRICE,
+        }
+    }
+}
+
+impl CurveKind {
+    pub fn validate(&self) -> Result<()> {
+        let valid = match *self {
+            CurveKind::Linear { k, base_price } => Self::valid_linear(k, base_price),
+            CurveKind::Exponential {
+                initial_price,
+                growth_rate,
+                step,
+            } => {
+                initial_price != 0
+                    && growth_rate != 0
+                    && growth_rate as u128 <= K_PRECISION
+                    && step != 0
+            }
+            CurveKind::ConstantProduct {
+                virtual_quote_reserve,
+                virtual_droplet_reserve,
+            } => virtual_quote_reserve != 0 && virtual_droplet_reserve != 0,
+            CurveKind::Capped {
+                k,
+                base_price,
+                max_price,
+            } => Self::valid_linear(k, base_price) && max_price != 0,
+        };
+
+        if !valid {
+            return Err(ErrorCode::InvalidCurve.into());
+        }
+
+        Ok(())
+    }
+
+    fn valid_linear(k: u64, base_price: u64) -> bool {
+        k != 0 && k <= MAX_K && base_price != 0 && base_price <= MAX_BASE_DROPLET_PRICE
+    }
+
+    // Cost of the supply range [0, supply), the cost of a trade is the difference of two
+    // of these so the ends round in opposite directions
+    fn cumulative_cost(&self, supply: u64, rounding: Rounding) -> Result<u128> {
+        match *self {
+            CurveKind::Linear { k, base_price } => {
+                linear_cumulative_cost(k, base_price, supply, rounding)
+            }
+            CurveKind::Exponential {
+                initial_price,
+                growth_rate,
+                step,
+            } => {
+                let growth = K_PRECISION + growth_rate as u128;
+                let steps = supply / step;
+                let partial = (supply % step) as u128;
+
+                // step * (g^n - 1) / (g - 1) full steps, then the partial step at g^n
+                let multiplier = math::pow_fixed(growth, steps, K_PRECISION, rounding)?;
+                let full_steps = math::mul_div(
+                    step as u128,
+                    multiplier - K_PRECISION,
+                    growth_rate as u128,
+                    rounding,
+                )?;
+                let partial_step = math::mul_div(partial, multiplier, K_PRECISION, rounding)?;
+                let weighted_supply = full_steps
+                    .checked_add(partial_step)
+                    .ok_or(ErrorCode::InvalidCalculation)?;
+
+                math::mul_div(
+                    weighted_supply,
+                    initial_price as u128,
+                    DROPLET_MINT_DECIMALS as u128,
+                    rounding,
+                )
+            }
+            CurveKind::ConstantProduct {
+                virtual_quote_reserve,
+                virtual_droplet_reserve,
+            } => {
+                if supply >= virtual_droplet_reserve {
+                    return Err(ErrorCode::InvalidCalculation.into());
+                }
+
+                // The quote reserve at supply, less the starting reserve
+                let invariant = virtual_quote_reserve as u128 * virtual_droplet_reserve as u128;
+                let quote_reserve = math::mul_div(
+                    invariant,
+                    1,
+                    (virtual_droplet_reserve - supply) as u128,
+                    rounding,
+                )?;
+                Ok(quote_reserve - virtual_quote_reserve as u128)
+            }
+            CurveKind::Capped {
+                k,
+                base_price,
+                max_price,
+            } => {
+                let cap_supply = capped_supply(k, base_price, max_price)?;
+                if supply <= cap_supply {
+                    return linear_cumulative_cost(k, base_price, supply, rounding);
+                }
+
+                let linear_cost = linear_cumulative_cost(k, base_price, cap_supply, rounding)?;
+                let flat_cost = math::mul_div(
+                    (supply - cap_supply) as u128,
+                    max_price as u128,
+                    DROPLET_MINT_DECIMALS as u128,
+                    rounding,
+                )?;
+                linear_cost
+                    .checked_add(flat_cost)
+                    .ok_or(ErrorCode::InvalidCalculation.into())
+            }
+        }
+    }
+
+    // Largest supply whose cumulative cost, rounded up, is at most `cost`. Each curve
+    // inverts in closed form and the result is checked against cumulative_cost, which
+    // only steps further where the inverse rounds differently.
+    fn supply_within(&self, cost: u128) -> Result<u64> {
+        let estimate = match *self {
+            CurveKind::Linear { k, base_price } => linear_supply_within(k, base_price, cost),
+            CurveKind::Exponential {
+                initial_price,
+                growth_rate,
+                step,
+            } => exponential_supply_within(initial_price, growth_rate, step, cost)?,
+            CurveKind::ConstantProduct {
+                virtual_quote_reserve,
+                virtual_droplet_reserve,
+            } => {
+                // The droplet reserve left once the quote reserve grows by `cost`
+                let invariant = virtual_quote_reserve as u128 * virtual_droplet_reserve as u128;
+                let quote_reserve = cost.saturating_add(virtual_quote_reserve as u128);
+                let droplet_reserve = math::mul_div(invariant, 1, quote_reserve, Rounding::Up)?;
+                virtual_droplet_reserve - droplet_reserve as u64
+            }
+            CurveKind::Capped {
+                k,
+                base_price,
+                max_price,
+            } => {
+                let cap_supply = capped_supply(k, base_price, max_price)?;
+                match linear_cumulative_cost(k, base_price, cap_supply, Rounding::Up) {
+                    Ok(linear_cost) if cost >= linear_cost => {
+                        let flat_supply = math::mul_div(
+                            cost - linear_cost,
+                            DROPLET_MINT_DECIMALS as u128,
+                            max_price as u128,
+                            Rounding::Down,
+                        );
+                        cap_supply.saturating_add(saturate(flat_supply))
+                    }
+                    _ => linear_supply_within(k, base_price, cost).min(cap_supply),
+                }
+            }
+        };
+
+        math::last_fitting(estimate, u64::MAX, |supply| {
+            Ok(self
+                .cumulative_cost(supply, Rounding::Up)
+                .is_ok_and(|supply_cost| supply_cost <= cost))
+        })?
+        .ok_or(ErrorCode::InvalidCalculation.into())
+    }
+}
+
+impl BondingCurve for CurveKind {
+    fn buy_cost(&self, supply: u64, amount: u64) -> Result<u64> {
+        if amount == 0 {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+
+        let ending_supply = supply
+            .checked_add(amount)
+            .ok_or(ErrorCode::InvalidCalculation)?;
+        let cost = self
+            .cumulative_cost(ending_supply, Rounding::Up)?
+            .saturating_sub(self.cumulative_cost(supply, Rounding::Down)?);
+        math::to_u64(cost)
+    }
+
+    fn sell_proceeds(&self, supply: u64, amount: u64) -> Result<u64> {
+        if amount == 0 || amount > supply {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+
+        let proceeds = self
+            .cumulative_cost(supply, Rounding::Down)?
+            .saturating_sub(self.cumulative_cost(supply - amount, Rounding::Up)?);
+        math::to_u64(proceeds)
+    }
+
+    fn spot_price(&self, supply: u64) -> Result<u64> {
+        let price = match *self {
+            CurveKind::Linear { k, base_price } => linear_spot_price(k, base_price, supply)?,
+            CurveKind::Exponential {
+                initial_price,
+                growth_rate,
+                step,
+            } => {
+                let growth = K_PRECISION + growth_rate as u128;
+                let multiplier =
+                    math::pow_fixed(growth, supply / step, K_PRECISION, Rounding::Down)?;
+                math::mul_div(
+                    initial_price as u128,
+                    multiplier,
+                    K_PRECISION,
+                    Rounding::Down,
+                )?
+            }
+            CurveKind::ConstantProduct {
+                virtual_quote_reserve,
+                virtual_droplet_reserve,
+            } => {
+                if supply >= virtual_droplet_reserve {
+                    return Err(ErrorCode::InvalidCalculation.into());
+                }
+
+                // quote_reserve / droplet_reserve, with quote_reserve = invariant / droplet_reserve
+                let invariant = virtual_quote_reserve as u128 * virtual_droplet_reserve as u128;
+                let droplet_reserve = (virtual_droplet_reserve - supply) as u128;
+                math::mul_div(
+                    invariant,
+                    DROPLET_MINT_DECIMALS as u128,
+                    droplet_reserve * droplet_reserve,
+                    Rounding::Down,
+                )?
+            }
+            CurveKind::Capped {
+                k,
+                base_price,
+                max_price,
+            } => linear_spot_price(k, base_price, supply)?.min(max_price as u128),
+        };
+        math::to_u64(price)
+    }
+
+    fn buy_amount(&self, supply: u64, cost: u64) -> Result<u64> {
+        let budget = self
+            .cumulative_cost(supply, Rounding::Down)?
+            .checked_add(cost as u128)
+            .ok_or(ErrorCode::InvalidCalculation)?;
+        Ok(self.supply_within(budget)?.saturating_sub(supply))
+    }
+
+    fn sell_amount(&self, supply: u64, proceeds: u64) -> Result<u64> {
+        if proceeds == 0 {
+            return Ok(0);
+        }
+
+        // Selling everything is the most any sale takes
+        let remaining_cost = self
+            .cumulative_cost(supply, Rounding::Down)?
+            .checked_sub(proceeds as u128)
+            .ok_or(ErrorCode::AmountNotEnough)?;
+        Ok(supply - self.supply_within(remaining_cost)?.min(supply))
+    }
+}
+
+// k * base_price * supply^2 / 2
+fn linear_cumulative_cost(
+    k: u64,
+    base_price: u64,
+    supply: u64,
+    rounding: Rounding,
+) -> Result<u128> {
+    let scaled_price = k as u128 * base_price as u128;
+    let supply_area = supply as u128 * supply as u128;
+    math::mul_div(supply_area, scaled_price, 2 * K_PRECISION, rounding)
+}
+
+fn linear_spot_price(k: u64, base_price: u64, supply: u64) -> Result<u128> {
+    let scaled_price = k as u128 * base_price as u128;
+    math::mul_div(
+        supply as u128 * DROPLET_MINT_DECIMALS as u128,
+        scaled_price,
+        K_PRECISION,
+        Rounding::Down,
+    )
+}
+
+// Inverts linear_cumulative_cost, exact for costs rounded up
+fn linear_supply_within(k: u64, base_price: u64, cost: u128) -> u64 {
+    let scaled_price = k as u128 * base_price as u128;
+    let supply_area = math::mul_div(cost, 2 * K_PRECISION, scaled_price, Rounding::Down);
+    // The root of a u128 always fits a u64
+    supply_area.map_or(u64::MAX, |area| math::isqrt(area) as u64)
+}
+
+// Full steps weigh step * (g^n - 1) / (g - 1) base units, so n is the largest whose g^n
+// stays under the weighted supply `cost` pays for. It's built bit by bit from the
+// squared powers pow_fixed multiplies, then checked against the full step weight itself,
+// and the rest of the weight is spent in the partial step.
+fn exponential_supply_within(
+    initial_price: u64,
+    growth_rate: u64,
+    step: u64,
+    cost: u128,
+) -> Result<u64> {
+    let growth = K_PRECISION + growth_rate as u128;
+    let weighted_supply = math::mul_div(
+        cost,
+        DROPLET_MINT_DECIMALS as u128,
+        initial_price as u128,
+        Rounding::Down,
+    )
+    .unwrap_or(u128::MAX);
+    let full_steps = |steps: u64| -> Result<u128> {
+        let multiplier = math::pow_fixed(growth, steps, K_PRECISION, Rounding::Up)?;
+        math::mul_div(
+            step as u128,
+            multiplier - K_PRECISION,
+            growth_rate as u128,
+            Rounding::Up,
+        )
+    };
+
+    let max_multiplier = math::mul_div(
+        weighted_supply,
+        growth_rate as u128,
+        step as u128,
+        Rounding::Down,
+    )
+    .map_or(u128::MAX, |growth| growth.saturating_add(K_PRECISION));
+    let mut powers = [0u128; u64::BITS as usize];
+    let mut count = 0;
+    let mut power = growth;
+    while count < powers.len() && power <= max_multiplier {
+        powers[count] = power;
+        count += 1;
+        match math::mul_div(power, power, K_PRECISION, Rounding::Up) {
+            Ok(squared) => power = squared,
+            Err(_) => break,
+        }
+    }
+    let (mut estimate, mut multiplier) = (0u64, K_PRECISION);
+    for bit in (0..count).rev() {
+        if let Ok(next) = math::mul_div(multiplier, powers[bit], K_PRECISION, Rounding::Up) {
+            if next <= max_multiplier {
+                multiplier = next;
+                estimate |= 1 << bit;
+            }
+        }
+    }
+
+    let steps = math::last_fitting(estimate, u64::MAX / step, |steps| {
+        Ok(full_steps(steps).is_ok_and(|weight| weight <= weighted_supply))
+    })?
+    .ok_or(ErrorCode::InvalidCalculation)?;
+    let multiplier = math::pow_fixed(growth, steps, K_PRECISION, Rounding::Up)?;
+    let partial = math::mul_div(
+        weighted_supply - full_steps(steps)?,
+        K_PRECISION,
+        multiplier,
+        Rounding::Down,
+    )
+    .map_or(step - 1, |partial| partial.min(step as u128 - 1) as u64);
+    Ok((steps * step).saturating_add(partial))
+}
+
+fn saturate(value: Result<u128>) -> u64 {
+    value.map_or(u64::MAX, |value| value.min(u64::MAX as u128) as u64)
+}
+
+// Supply at which the linear whole droplet price reaches max_price
+fn capped_supply(k: u64, base_price: u64, max_price: u64) -> Result<u64> {
+    let scaled_price = k as u128 * base_price as u128 * DROPLET_MINT_DECIMALS as u128;
+    let supply = math::mul_div(max_price as u128, K_PRECISION, scaled_price, Rounding::Down)?;
+    Ok(supply.min(u64::MAX as u128) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::MAX_DROPLET_SUPPLY;
+
+    const CURVES: [CurveKind; 4] = [
+        CurveKind::Linear {
+            k: K,
+            base_price: BASE_DROPLET_PRICE,
+        },
+        CurveKind::Exponential {
+            initial_price: 1_000,
+            growth_rate: 10_000_000, // 1% per step
+            step: 1_000_000,
+        },
+        CurveKind::ConstantProduct {
+            virtual_quote_reserve: 30_000_000_000,
+            virtual_droplet_reserve: 1_073_000_000_000_000,
+        },
+        CurveKind::Capped {
+            k: K,
+            base_price: BASE_DROPLET_PRICE,
+            max_price: 5_000_000_000,
+        },
+    ];
+
+    #[test]
+    fn test_curves_round_trip() {
+        for curve in CURVES {
+            curve.validate().unwrap();
+            for supply in [0u64, 1, 999_999, 1_000_000, 123_456_789] {
+                for amount in [1u64, 7, 1_000_000, 2_500_001] {
+                    let cost = curve.buy_cost(supply, amount).unwrap();
+                    let proceeds = curve.sell_proceeds(supply + amount, amount).unwrap();
+                    assert!(proceeds <= cost, "{:?} {} {}", curve, supply, amount);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_curves_are_monotonic() {
+        for curve in CURVES {
+            let mut previous_price = 0;
+            let mut previous_cost = 0;
+            for supply in (0..50_000_000u64).step_by(999_983) {
+                let price = curve.spot_price(supply).unwrap();
+                let cost = curve.buy_cost(supply, 1_000_000).unwrap();
+                assert!(price >= previous_price, "{:?} {}", curve, supply);
+                // Costs can wobble by the one unit that buys round up
+                assert!(cost + 1 >= previous_cost, "{:?} {}", curve, supply);
+                previous_price = price;
+                previous_cost = cost;
+            }
+        }
+    }
+
+    #[test]
+    fn test_curves_split_trades() {
+        // Buying in two legs never costs less than buying at once
+        for curve in CURVES {
+            let whole = curve.buy_cost(10_000_000, 3_000_000).unwrap();
+            let legs = curve.buy_cost(10_000_000, 1_000_001).unwrap()
+                + curve.buy_cost(11_000_001, 1_999_999).unwrap();
+            assert!(legs >= whole);
+            assert!(legs - whole <= 2);
+        }
+    }
+
+    #[test]
+    fn test_curves_invert_at_supply_edges() {
+        let cap_supply = capped_supply(K, BASE_DROPLET_PRICE, 5_000_000_000).unwrap();
+        let steep = CurveKind::Exponential {
+            initial_price: 1,
+            growth_rate: 1,
+            step: 1,
+        };
+        let shallow = CurveKind::Exponential {
+            initial_price: 1_000_000_000,
+            growth_rate: K_PRECISION as u64,
+            step: 1_000_000,
+        };
+        let mut curves = CURVES.to_vec();
+        curves.extend([steep, shallow]);
+
+        for curve in curves {
+            let reserve = match curve {
+                CurveKind::ConstantProduct {
+                    virtual_droplet_reserve,
+                    ..
+                } => virtual_droplet_reserve,
+                _ => u64::MAX,
+            };
+            let supplies = [
+                0,
+                1,
+                cap_supply - 1,
+                cap_supply,
+                cap_supply + 1,
+                999_999,
+                1_000_000,
+                123_456_789,
+                MAX_DROPLET_SUPPLY,
+                reserve - 1,
+            ];
+            for supply in supplies {
+                if curve.cumulative_cost(supply, Rounding::Down).is_err() {
+                    continue;
+                }
+                for cost in [
+                    0,
+                    1,
+                    999,
+                    1_000_000,
+                    123_456_789_012,
+                    u64::MAX / 2,
+                    u64::MAX,
+                ] {
+                    // The amount buys within the cost and one more droplet doesn't
+                    let amount = curve.buy_amount(supply, cost).unwrap();
+                    if amount != 0 {
+                        assert!(curve.buy_cost(supply, amount).unwrap() <= cost);
+                    }
+                    let over = amount
+                        .checked_add(1)
+                        .and_then(|over| curve.buy_cost(supply, over).ok());
+                    assert!(
+                        over.is_none_or(|over| over > cost),
+                        "{:?} {}",
+                        curve,
+                        supply
+                    );
+
+                    // The amount sells for the proceeds and one droplet fewer doesn't
+                    let amount = match curve.sell_amount(supply, cost) {
+                        Ok(amount) => amount,
+                        Err(error) => {
+                            assert_eq!(error, ErrorCode::AmountNotEnough.into());
+                            assert!(curve.sell_proceeds(supply, supply).unwrap_or(0) < cost);
+                            continue;
+                        }
+                    };
+                    // Proceeds too large for a u64 reach any cost
+                    if cost != 0 {
+                        let proceeds = curve.sell_proceeds(supply, amount).ok();
+                        assert!(proceeds.is_none_or(|proceeds| proceeds >= cost));
+                    }
+                    if amount > 1 {
+                        let under = curve.sell_proceeds(supply, amount - 1).unwrap();
+                        assert!(under < cost, "{:?} {} {}", curve, supply, cost);
+                    }
+                }
+            }
+        }
+
+        // Edges with known answers
+        let linear = CURVES[0];
+        assert_eq!(linear.buy_amount(0, 0).unwrap(), 0);
+        assert_eq!(linear.buy_amount(0, 1).unwrap(), 1);
+        assert_eq!(linear.buy_amount(3, 3).unwrap(), 0);
+        assert_eq!(linear.buy_amount(3, 4).unwrap(), 1);
+        assert_eq!(linear.sell_amount(4, 3).unwrap(), 1);
+        assert_eq!(linear.sell_amount(4, 8).unwrap(), 4);
+        assert!(linear.sell_amount(4, 9).is_err());
+        let constant_product = CurveKind::ConstantProduct {
+            virtual_quote_reserve: 1_000,
+            virtual_droplet_reserve: 1_000,
+        };
+        assert_eq!(constant_product.buy_amount(0, 1_000).unwrap(), 500);
+        assert_eq!(constant_product.buy_amount(0, u64::MAX).unwrap(), 999);
+        assert_eq!(constant_product.sell_amount(500, 1_000).unwrap(), 500);
+        let capped = CURVES[3];
+        assert_eq!(
+            capped.buy_amount(cap_supply * 2, 5_000_000_000).unwrap(),
+            1_000_000
+        );
+    }
+
+    #[test]
+    fn test_exponential_compounds_per_step() {
+        let curve = CURVES[1];
+        assert_eq!(curve.spot_price(0).unwrap(), 1_000);
+        assert_eq!(curve.spot_price(999_999).unwrap(), 1_000);
+        assert_eq!(curve.spot_price(1_000_000).unwrap(), 1_010);
+        assert_eq!(curve.spot_price(2_000_000).unwrap(), 1_020);
+
+        // Two whole steps cost 1000 + 1010
+        assert_eq!(curve.buy_cost(0, 2_000_000).unwrap(), 2_010);
+    }
+
+    #[test]
+    fn test_constant_product_reserves() {
+        let curve = CurveKind::ConstantProduct {
+            virtual_quote_reserve: 1_000,
+            virtual_droplet_reserve: 1_000,
+        };
+        // Buying half the droplet reserve doubles the quote reserve
+        assert_eq!(curve.buy_cost(0, 500).unwrap(), 1_000);
+        assert_eq!(curve.sell_proceeds(500, 500).unwrap(), 1_000);
+        assert_eq!(curve.spot_price(0).unwrap(), DROPLET_MINT_DECIMALS);
+        assert!(curve.buy_cost(0, 1_000).is_err());
+    }
+
+    #[test]
+    fn test_capped_flattens_at_max_price() {
+        let curve = CURVES[3];
+        let cap_supply = capped_supply(K, BASE_DROPLET_PRICE, 5_000_000_000).unwrap();
+        assert_eq!(cap_supply, 5_000);
+        assert_eq!(curve.spot_price(cap_supply).unwrap(), 5_000_000_000);
+        assert_eq!(curve.spot_price(cap_supply * 1_000).unwrap(), 5_000_000_000);
+
+        // Past the cap a whole droplet costs exactly max_price
+        assert_eq!(
+            curve.buy_cost(cap_supply * 2, 1_000_000).unwrap(),
+            5_000_000_000
+        );
+    }
+
+    #[test]
+    fn test_invalid_curves() {
+        let invalid = [
+            CurveKind::Linear {
+                k: 0,
+                base_price: 1,
+            },
+            CurveKind::Linear {
+                k: MAX_K + 1,
+                base_price: 1,
+            },
+            CurveKind::Exponential {
+                initial_price: 1,
+                growth_rate: 0,
+                step: 1,
+            },
+            CurveKind::Exponential {
+                initial_price: 1,
+                growth_rate: 1,
+                step: 0,
+            },
+            CurveKind::ConstantProduct {
+                virtual_quote_reserve: 0,
+                virtual_droplet_reserve: 1,
+            },
+            CurveKind::Capped {
+                k: K,
+                base_price: 1,
+                max_price: 0,
+            },
+        ];
+        for curve in invalid {
+            assert!(curve.validate().is_err());
+        }
+    }
+}
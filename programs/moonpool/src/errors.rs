@@ -11,12 +11,6 @@ pub enum ErrorCode {
     #[msg("Maturity date is invalid.")]
     InvalidMaturityDate,
 
-    #[msg("Pool is not activated.")]
-    PoolNotActivated,
-
-    #[msg("Pool is already activated.")]
-    PoolAlreadyActivated,
-
     #[msg("Lock period is not over.")]
     LockPeriodNotOver,
 
@@ -29,24 +23,12 @@ pub enum ErrorCode {
     #[msg("This amount is invalid.")]
     InvalidAmount,
 
-    #[msg("This stake is already running.")]
-    AlreadyInitialized,
-
     #[msg("Unauthorized.")]
     Unauthorized,
 
     #[msg("Invalid account info")]
     InvalidAccount,
 
-    #[msg("Invalid mint.")]
-    InvalidMint,
-
-    #[msg("Vault is not initialized.")]
-    VaultNotInitialized,
-
-    #[msg("No rewards to claim.")]
-    NoRewardsToClaim,
-
     #[msg("Pool name is too long.")]
     InvalidPoolName,
 
@@ -55,4 +37,100 @@ pub enum ErrorCode {
 
     #[msg("Exceeds maximum supply.")]
     ExceedsMaximumSupply,
+
+    #[msg("Protocol config value is out of range.")]
+    InvalidConfig,
+
+    #[msg("Fee vault balance is insufficient.")]
+    InsufficientFeeBalance,
+
+    #[msg("No admin transfer is pending.")]
+    NoPendingAdmin,
+
+    #[msg("Admin transfer delay has not elapsed.")]
+    AdminTransferDelayNotElapsed,
+
+    #[msg("Protocol is paused.")]
+    ProtocolPaused,
+
+    #[msg("Pool is halted.")]
+    PoolHalted,
+
+    #[msg("Raise duration is invalid.")]
+    InvalidRaiseDuration,
+
+    #[msg("Raise start is invalid.")]
+    InvalidRaiseStart,
+
+    #[msg("Pool raise goal has been reached.")]
+    PoolFilled,
+
+    #[msg("Pool status does not allow this action.")]
+    InvalidPoolStatus,
+
+    #[msg("Asset accounts do not match the pool.")]
+    InvalidAssetAccounts,
+
+    #[msg("Pool still has outstanding droplets.")]
+    PoolNotFullyRedeemed,
+
+    #[msg("Pool still has assets.")]
+    PoolHasAssets,
+
+    #[msg("Vault is not empty.")]
+    VaultNotEmpty,
+
+    #[msg("Rescind amount exceeds the member's contribution.")]
+    RescindExceedsContribution,
+
+    #[msg("Member still holds droplets.")]
+    MemberBalanceNotZero,
+
+    #[msg("Contribution is below the pool minimum.")]
+    ContributionBelowMinimum,
+
+    #[msg("Contribution exceeds the per-wallet maximum.")]
+    WalletContributionCapExceeded,
+
+    #[msg("Contribution limits are invalid.")]
+    InvalidContributionLimits,
+
+    #[msg("Raise is allowlisted, a proof is required.")]
+    AllowlistProofRequired,
+
+    #[msg("Allowlist proof is invalid.")]
+    InvalidAllowlistProof,
+
+    #[msg("Contribution exceeds the allowlist allocation.")]
+    AllowlistAllocationExceeded,
+
+    #[msg("Raise phases are invalid.")]
+    InvalidRaisePhases,
+
+    #[msg("Bonding curve parameters are invalid.")]
+    InvalidCurve,
+
+    #[msg("Price moved beyond the allowed slippage.")]
+    SlippageExceeded,
+
+    #[msg("Transaction deadline has passed.")]
+    TransactionExpired,
+
+    #[msg("Pool vault can't cover this sale.")]
+    PoolInsolvent,
+
+    #[msg("Owner fee exceeds the protocol maximum.")]
+    OwnerFeeTooHigh,
+
+    #[msg("Metadata uri is too long.")]
+    InvalidMetadataUri,
+
+    #[msg("An admin transfer is pending.")]
+    AdminTransferPending,
+
+    #[msg("The pool holds no owner liquidity to withdraw.")]
+    NoOwnerLiquidity,
+
+    #[msg("What's left of the raise is reserved for guaranteed allocations.")]
+    RaiseReservedForGuarantees,
 }
@@ -0,0 +1,152 @@
+use crate::instructions::UpdateConfigParams;
+use crate::state::PoolStatus;
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub params: UpdateConfigParams,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeesWithdrawn {
+    pub admin: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub total_withdrawn: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct QuoteFeesWithdrawn {
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub total_withdrawn: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub eta: i64,
+}
+
+#[event]
+pub struct AdminProposalCancelled {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProtocolPauseSet {
+    pub admin: Pubkey,
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolHaltSet {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub halted: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AllowlistSet {
+    pub pool: Pubkey,
+    pub allowlist_root: [u8; 32],
+    pub public_raise_at: i64,
+    pub guaranteed_reserve: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct Contributed {
+    pub pool: Pubkey,
+    pub contributor: Pubkey,
+    pub requested_amount: u64,
+    pub accepted_amount: u64,
+    pub refunded_amount: u64,
+    pub droplets_minted: u64,
+    pub total_raised: u64,
+    pub is_filled: bool,
+}
+
+#[event]
+pub struct RefundClaimed {
+    pub pool: Pubkey,
+    pub contributor: Pubkey,
+    pub droplets_burned: u64,
+    pub refund_amount: u64,
+}
+
+#[event]
+pub struct PoolStatusChanged {
+    pub pool: Pubkey,
+    pub previous_status: PoolStatus,
+    pub status: PoolStatus,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolSettled {
+    pub pool: Pubkey,
+    pub settled_supply: u64,
+    pub settled_vault_balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct Redeemed {
+    pub pool: Pubkey,
+    pub holder: Pubkey,
+    pub droplets_burned: u64,
+    pub vault_amount: u64,
+    pub assets_redeemed: u32,
+}
+
+#[event]
+pub struct AssetClosed {
+    pub pool: Pubkey,
+    pub asset: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct ContributionRescinded {
+    pub pool: Pubkey,
+    pub contributor: Pubkey,
+    pub amount: u64,
+    pub rescind_fee: u64,
+    pub droplets_burned: u64,
+    pub total_raised: u64,
+}
+
+#[event]
+pub struct LiquidityAdded {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub droplet_liquidity: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidityWithdrawn {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,245 @@
+use crate::errors::ErrorCode;
+use crate::math::{self, Rounding};
+use crate::state::BPS_DENOMINATOR;
+use anchor_lang::prelude::*;
+
+/// How buy and contribute fees relate to the amount the trader names.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default, InitSpace,
+)]
+pub enum FeeMode {
+    // Fees are charged on top of the amount, which is credited in full
+    #[default]
+    Exclusive,
+    // Fees are carved out of the amount, only the remainder is credited
+    Inclusive,
+}
+
+/// `gross` is what moves from the payer or the vault, `net` is what's left for the
+/// trade once both fees are taken out. `gross == net + pool_owner_fee + program_fee`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeBreakdown {
+    pub gross: u64,
+    pub pool_owner_fee: u64,
+    pub program_fee: u64,
+    pub net: u64,
+}
+
+impl FeeBreakdown {
+    /// Fees at their rates on `net`, added on top of it.
+    pub fn exclusive(net: u64, owner_bps: u16, program_bps: u16) -> Result<Self> {
+        let (pool_owner_fee, program_fee) = split_fee(net, owner_bps, program_bps)?;
+        let gross = net
+            .checked_add(pool_owner_fee)
+            .and_then(|total| total.checked_add(program_fee))
+            .ok_or(ErrorCode::InvalidCalculation)?;
+        Ok(FeeBreakdown {
+            gross,
+            pool_owner_fee,
+            program_fee,
+            net,
+        })
+    }
+
+    /// Fees at their rates on `gross`, taken out of it. Sell proceeds always use this.
+    pub fn inclusive(gross: u64, owner_bps: u16, program_bps: u16) -> Result<Self> {
+        let (pool_owner_fee, program_fee) = split_fee(gross, owner_bps, program_bps)?;
+        let net = gross
+            .checked_sub(pool_owner_fee)
+            .and_then(|net| net.checked_sub(program_fee))
+            .ok_or(ErrorCode::InvalidCalculation)?;
+        Ok(FeeBreakdown {
+            gross,
+            pool_owner_fee,
+            program_fee,
+            net,
+        })
+    }
+
+    /// The smallest inclusive charge that leaves at least `net`.
+    pub fn inclusive_for_net(net: u64, owner_bps: u16, program_bps: u16) -> Result<Self> {
+        let total_bps = owner_bps as u64 + program_bps as u64;
+        if total_bps >= BPS_DENOMINATOR {
+            return Err(ErrorCode::InvalidCalculation.into());
+        }
+
+        // Start from the exact gross-up, then step past any fee rounded up against it
+        let mut gross = math::mul_div(
+            net as u128,
+            BPS_DENOMINATOR as u128,
+            (BPS_DENOMINATOR - total_bps) as u128,
+            Rounding::Up,
+        )
+        .and_then(math::to_u64)?;
+        loop {
+            let breakdown = Self::inclusive(gross, owner_bps, program_bps)?;
+            if breakdown.net >= net {
+                return Ok(breakdown);
+            }
+            gross = gross
+                .checked_add(net - breakdown.net)
+                .ok_or(ErrorCode::InvalidCalculation)?;
+        }
+    }
+
+    /// Charges a buy or contribution crediting `net` in full.
+    pub fn for_net(mode: FeeMode, net: u64, owner_bps: u16, program_bps: u16) -> Result<Self> {
+        match mode {
+            FeeMode::Exclusive => Self::exclusive(net, owner_bps, program_bps),
+            FeeMode::Inclusive => Self::inclusive_for_net(net, owner_bps, program_bps),
+        }
+    }
+}
+
+// The combined fee rounds up once and is split by rate, the program taking the rounding.
+// Rounding each fee up on its own could charge a few base units more than they're worth.
+fn split_fee(value: u64, owner_bps: u16, program_bps: u16) -> Result<(u64, u64)> {
+    let total_bps = owner_bps as u64 + program_bps as u64;
+    if total_bps == 0 {
+        return Ok((0, 0));
+    }
+
+    let total = math::mul_div(
+        value as u128,
+        total_bps as u128,
+        BPS_DENOMINATOR as u128,
+        Rounding::Up,
+    )
+    .and_then(math::to_u64)?;
+    let pool_owner_fee = math::mul_div(
+        total as u128,
+        owner_bps as u128,
+        total_bps as u128,
+        Rounding::Down,
+    )
+    .and_then(math::to_u64)?;
+    Ok((pool_owner_fee, total - pool_owner_fee))
+}
+
+// Fees round up, so a trade never pays less than its rate
+pub fn bps_fee(value: u64, bps: u16) -> Result<u64> {
+    math::mul_div(
+        value as u128,
+        bps as u128,
+        BPS_DENOMINATOR as u128,
+        Rounding::Up,
+    )
+    .and_then(math::to_u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bps_fee() {
+        assert_eq!(bps_fee(10_000, 1).unwrap(), 1);
+        assert_eq!(bps_fee(10_001, 1).unwrap(), 2);
+        assert_eq!(bps_fee(1, 1).unwrap(), 1);
+        assert_eq!(bps_fee(0, 1_000).unwrap(), 0);
+        assert_eq!(bps_fee(12_345, 0).unwrap(), 0);
+        assert_eq!(bps_fee(u64::MAX, 10_000).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn test_exclusive() {
+        let breakdown = FeeBreakdown::exclusive(1_000_000, 250, 100).unwrap();
+        assert_eq!(
+            breakdown,
+            FeeBreakdown {
+                gross: 1_035_000,
+                pool_owner_fee: 25_000,
+                program_fee: 10_000,
+                net: 1_000_000,
+            }
+        );
+
+        // The fees round up once between them
+        let breakdown = FeeBreakdown::exclusive(1, 1, 1).unwrap();
+        assert_eq!((breakdown.gross, breakdown.net), (2, 1));
+        assert_eq!((breakdown.pool_owner_fee, breakdown.program_fee), (0, 1));
+
+        assert_eq!(FeeBreakdown::exclusive(0, 100, 100).unwrap().gross, 0);
+        assert!(FeeBreakdown::exclusive(u64::MAX, 1, 0).is_err());
+    }
+
+    #[test]
+    fn test_inclusive() {
+        let breakdown = FeeBreakdown::inclusive(1_000_000, 250, 100).unwrap();
+        assert_eq!(
+            breakdown,
+            FeeBreakdown {
+                gross: 1_000_000,
+                pool_owner_fee: 25_000,
+                program_fee: 10_000,
+                net: 965_000,
+            }
+        );
+
+        let breakdown = FeeBreakdown::inclusive(3, 1, 1).unwrap();
+        assert_eq!((breakdown.pool_owner_fee, breakdown.net), (0, 2));
+
+        // Tiny trades still price, with the rounding never past the gross
+        for gross in 0..=3 {
+            let breakdown = FeeBreakdown::inclusive(gross, 1_000, 1_000).unwrap();
+            assert_eq!(breakdown.net, gross.saturating_sub(1));
+        }
+        assert_eq!(FeeBreakdown::inclusive(1, 0, 0).unwrap().net, 1);
+        assert!(FeeBreakdown::inclusive(1, 10_000, 1).is_err());
+    }
+
+    #[test]
+    fn test_split_fee() {
+        // Split by rate, with the rounding left to the program
+        assert_eq!(split_fee(1_000_000, 250, 100).unwrap(), (25_000, 10_000));
+        assert_eq!(split_fee(10_001, 300, 100).unwrap(), (300, 101));
+        assert_eq!(split_fee(1, 0, 1).unwrap(), (0, 1));
+        assert_eq!(split_fee(1, 1, 0).unwrap(), (1, 0));
+        assert_eq!(split_fee(12_345, 0, 0).unwrap(), (0, 0));
+
+        // Net proceeds never drop as the gross grows
+        let mut previous = 0;
+        for gross in 0..5_000 {
+            let net = FeeBreakdown::inclusive(gross, 250, 100).unwrap().net;
+            assert!(net >= previous);
+            previous = net;
+        }
+    }
+
+    #[test]
+    fn test_inclusive_for_net() {
+        for (owner_bps, program_bps) in [(0, 0), (100, 100), (250, 100), (1, 1), (1_000, 1_000)] {
+            for net in [1, 2, 99, 1_000, 123_457, 1_000_000_000] {
+                let breakdown =
+                    FeeBreakdown::inclusive_for_net(net, owner_bps, program_bps).unwrap();
+                assert!(breakdown.net >= net);
+                assert_eq!(
+                    breakdown.gross,
+                    breakdown.net + breakdown.pool_owner_fee + breakdown.program_fee
+                );
+
+                // One less wouldn't cover it
+                let smaller = FeeBreakdown::inclusive(breakdown.gross - 1, owner_bps, program_bps);
+                assert!(smaller.map_or(true, |smaller| smaller.net < net));
+            }
+        }
+
+        assert!(FeeBreakdown::inclusive_for_net(1, 5_000, 5_000).is_err());
+    }
+
+    #[test]
+    fn test_for_net() {
+        let exclusive = FeeBreakdown::for_net(FeeMode::Exclusive, 1_000_000, 250, 100).unwrap();
+        assert_eq!(exclusive.net, 1_000_000);
+        assert_eq!(exclusive.gross, 1_035_000);
+
+        let inclusive = FeeBreakdown::for_net(FeeMode::Inclusive, 1_000_000, 250, 100).unwrap();
+        assert!(inclusive.net >= 1_000_000);
+        assert_eq!(
+            inclusive,
+            FeeBreakdown::inclusive(inclusive.gross, 250, 100).unwrap()
+        );
+        // Taking the rate out of the gross costs more than adding it to the net
+        assert!(inclusive.gross > exclusive.gross);
+    }
+}
@@ -0,0 +1,56 @@
+use crate::errors::ErrorCode;
+use crate::events::AdminTransferred;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [MOONPOOL_SEED],
+        bump,
+    )]
+    pub moonpool: Box<Account<'info, Moonpool>>,
+
+    #[account(
+        mut,
+        seeds = [FEE_VAULT_SEED],
+        bump,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    pub new_admin: Signer<'info>,
+}
+
+impl<'info> AcceptAdmin<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        if self.moonpool.pending_admin == Pubkey::default() {
+            return Err(ErrorCode::NoPendingAdmin.into());
+        }
+
+        if self.moonpool.pending_admin != self.new_admin.key() {
+            return Err(ErrorCode::Unauthorized.into());
+        }
+
+        let current_time = Clock::get()?.unix_timestamp;
+
+        if current_time < self.moonpool.pending_admin_eta {
+            return Err(ErrorCode::AdminTransferDelayNotElapsed.into());
+        }
+
+        let previous_admin = self.moonpool.admin;
+
+        self.moonpool.admin = self.new_admin.key();
+        self.fee_vault.admin = self.new_admin.key();
+        self.moonpool.pending_admin = Pubkey::default();
+        self.moonpool.pending_admin_eta = 0;
+
+        emit!(AdminTransferred {
+            previous_admin,
+            new_admin: self.new_admin.key(),
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use crate::errors::ErrorCode;
+use crate::events::AdminProposalCancelled;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelAdminProposal<'info> {
+    #[account(
+        mut,
+        seeds = [MOONPOOL_SEED],
+        bump,
+        constraint = moonpool.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub moonpool: Box<Account<'info, Moonpool>>,
+
+    pub admin: Signer<'info>,
+}
+
+impl<'info> CancelAdminProposal<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        if self.moonpool.pending_admin == Pubkey::default() {
+            return Err(ErrorCode::NoPendingAdmin.into());
+        }
+
+        let pending_admin = self.moonpool.pending_admin;
+
+        self.moonpool.pending_admin = Pubkey::default();
+        self.moonpool.pending_admin_eta = 0;
+
+        emit!(AdminProposalCancelled {
+            admin: self.admin.key(),
+            pending_admin,
+        });
+
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use crate::fees::FeeMode;
 use crate::state::*;
 use anchor_lang::prelude::*;
 
@@ -21,16 +22,45 @@ pub struct Initialize<'info> {
     )]
     pub fee_vault: Box<Account<'info, FeeVault>>,
 
+    #[account(
+        init,
+        seeds = [CONFIG_SEED],
+        bump,
+        payer = payer,
+        space = 8 + ProtocolConfig::INIT_SPACE,
+    )]
+    pub config: Box<Account<'info, ProtocolConfig>>,
+
     #[account(mut)]
     pub payer: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> Initialize<'info> {
-    pub fn handler(&mut self) -> Result<()> {
+    pub fn handler(&mut self, config_bump: u8) -> Result<()> {
         self.moonpool.admin = self.payer.key();
         self.moonpool.pools = 0;
+        self.moonpool.pending_admin = Pubkey::default();
+        self.moonpool.pending_admin_eta = 0;
+        self.moonpool.paused = false;
         self.fee_vault.admin = self.payer.key();
+
+        self.config.pool_creation_fee = POOL_CREATION_FEE;
+        self.config.pool_owner_fee_bps = POOL_OWNER_FEE_BPS;
+        self.config.max_pool_owner_fee_bps = MAX_POOL_OWNER_FEE_BPS;
+        self.config.program_fee_bps = PROGRAM_FEE_BPS;
+        self.config.fee_mode = FeeMode::Exclusive;
+        self.config.rescind_fee_bps = RESCIND_FEE_BPS;
+        self.config.k = K;
+        self.config.base_droplet_price = BASE_DROPLET_PRICE;
+        self.config.min_raise_duration = MIN_RAISE_DURATION;
+        self.config.max_raise_duration = MAX_RAISE_DURATION;
+        self.config.min_pool_term = MIN_POOL_TERM;
+        self.config.max_pool_term = MAX_POOL_TERM;
+        self.config.max_raise_start_delay = MAX_RAISE_START_DELAY;
+        self.config.admin_transfer_delay = ADMIN_TRANSFER_DELAY;
+        self.config.exits_open_when_paused = true;
+        self.config.bump = config_bump;
         Ok(())
     }
 }
@@ -1 +1,8 @@
+pub mod accept_admin;
+pub mod cancel_admin_proposal;
 pub mod initialize;
+pub mod propose_admin;
+pub mod set_paused;
+pub mod update_config;
+pub mod withdraw_fees;
+pub mod withdraw_quote_fees;
@@ -0,0 +1,48 @@
+use crate::errors::ErrorCode;
+use crate::events::AdminProposed;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [MOONPOOL_SEED],
+        bump,
+        constraint = moonpool.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub moonpool: Box<Account<'info, Moonpool>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, ProtocolConfig>>,
+
+    pub admin: Signer<'info>,
+}
+
+impl<'info> ProposeAdmin<'info> {
+    pub fn handler(&mut self, new_admin: Pubkey) -> Result<()> {
+        if new_admin == Pubkey::default() || new_admin == self.moonpool.admin {
+            return Err(ErrorCode::InvalidAccount.into());
+        }
+
+        let eta = Clock::get()?
+            .unix_timestamp
+            .checked_add(self.config.admin_transfer_delay)
+            .ok_or(ErrorCode::InvalidCalculation)?;
+
+        // A new proposal replaces any pending one and restarts the delay
+        self.moonpool.pending_admin = new_admin;
+        self.moonpool.pending_admin_eta = eta;
+
+        emit!(AdminProposed {
+            admin: self.admin.key(),
+            pending_admin: new_admin,
+            eta,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use crate::errors::ErrorCode;
+use crate::events::ProtocolPauseSet;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        seeds = [MOONPOOL_SEED],
+        bump,
+        constraint = moonpool.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub moonpool: Box<Account<'info, Moonpool>>,
+
+    pub admin: Signer<'info>,
+}
+
+impl<'info> SetPaused<'info> {
+    pub fn handler(&mut self, paused: bool) -> Result<()> {
+        self.moonpool.paused = paused;
+
+        emit!(ProtocolPauseSet {
+            admin: self.admin.key(),
+            paused,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
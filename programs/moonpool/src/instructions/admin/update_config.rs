@@ -0,0 +1,109 @@
+use crate::errors::ErrorCode;
+use crate::events::ConfigUpdated;
+use crate::fees::FeeMode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct UpdateConfigParams {
+    pub pool_creation_fee: Option<u64>,
+    pub pool_owner_fee_bps: Option<u16>,
+    pub max_pool_owner_fee_bps: Option<u16>,
+    pub program_fee_bps: Option<u16>,
+    pub fee_mode: Option<FeeMode>,
+    pub rescind_fee_bps: Option<u16>,
+    pub k: Option<u64>,
+    pub base_droplet_price: Option<u64>,
+    pub min_raise_duration: Option<i64>,
+    pub max_raise_duration: Option<i64>,
+    pub min_pool_term: Option<i64>,
+    pub max_pool_term: Option<i64>,
+    pub max_raise_start_delay: Option<i64>,
+    pub admin_transfer_delay: Option<i64>,
+    pub exits_open_when_paused: Option<bool>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        seeds = [MOONPOOL_SEED],
+        bump,
+        constraint = moonpool.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub moonpool: Box<Account<'info, Moonpool>>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, ProtocolConfig>>,
+
+    pub admin: Signer<'info>,
+}
+
+impl<'info> UpdateConfig<'info> {
+    pub fn handler(&mut self, params: UpdateConfigParams) -> Result<()> {
+        let config = &mut self.config;
+
+        if let Some(pool_creation_fee) = params.pool_creation_fee {
+            config.pool_creation_fee = pool_creation_fee;
+        }
+        if let Some(pool_owner_fee_bps) = params.pool_owner_fee_bps {
+            config.pool_owner_fee_bps = pool_owner_fee_bps;
+        }
+        if let Some(max_pool_owner_fee_bps) = params.max_pool_owner_fee_bps {
+            config.max_pool_owner_fee_bps = max_pool_owner_fee_bps;
+        }
+        if let Some(program_fee_bps) = params.program_fee_bps {
+            config.program_fee_bps = program_fee_bps;
+        }
+        if let Some(fee_mode) = params.fee_mode {
+            config.fee_mode = fee_mode;
+        }
+        if let Some(rescind_fee_bps) = params.rescind_fee_bps {
+            config.rescind_fee_bps = rescind_fee_bps;
+        }
+        if let Some(k) = params.k {
+            config.k = k;
+        }
+        if let Some(base_droplet_price) = params.base_droplet_price {
+            config.base_droplet_price = base_droplet_price;
+        }
+        if let Some(min_raise_duration) = params.min_raise_duration {
+            config.min_raise_duration = min_raise_duration;
+        }
+        if let Some(max_raise_duration) = params.max_raise_duration {
+            config.max_raise_duration = max_raise_duration;
+        }
+        if let Some(min_pool_term) = params.min_pool_term {
+            config.min_pool_term = min_pool_term;
+        }
+        if let Some(max_pool_term) = params.max_pool_term {
+            config.max_pool_term = max_pool_term;
+        }
+        if let Some(max_raise_start_delay) = params.max_raise_start_delay {
+            config.max_raise_start_delay = max_raise_start_delay;
+        }
+        if let Some(admin_transfer_delay) = params.admin_transfer_delay {
+            // A pending handover keeps the delay it was proposed under
+            if self.moonpool.pending_admin != Pubkey::default() {
+                return Err(ErrorCode::AdminTransferPending.into());
+            }
+            config.admin_transfer_delay = admin_transfer_delay;
+        }
+        if let Some(exits_open_when_paused) = params.exits_open_when_paused {
+            config.exits_open_when_paused = exits_open_when_paused;
+        }
+
+        config.validate()?;
+
+        emit!(ConfigUpdated {
+            admin: self.admin.key(),
+            params,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
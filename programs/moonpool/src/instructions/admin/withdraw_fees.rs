@@ -0,0 +1,66 @@
+use crate::errors::ErrorCode;
+use crate::events::FeesWithdrawn;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    #[account(
+        mut,
+        seeds = [FEE_VAULT_SEED],
+        bump,
+        constraint = fee_vault.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(mut)]
+    pub destination: SystemAccount<'info>,
+
+    pub admin: Signer<'info>,
+}
+
+impl<'info> WithdrawFees<'info> {
+    pub fn handler(&mut self, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+
+        // Only lamports above the rent-exempt minimum can leave the vault
+        let fee_vault_info = self.fee_vault.to_account_info();
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(fee_vault_info.data_len());
+        let available = fee_vault_info
+            .lamports()
+            .saturating_sub(rent_exempt_minimum);
+
+        if amount > available {
+            return Err(ErrorCode::InsufficientFeeBalance.into());
+        }
+
+        **fee_vault_info.try_borrow_mut_lamports()? = fee_vault_info
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InvalidCalculation)?;
+
+        **self
+            .destination
+            .to_account_info()
+            .try_borrow_mut_lamports()? = self
+            .destination
+            .to_account_info()
+            .lamports()
+            .checked_add(amount)
+            .ok_or(ErrorCode::InvalidCalculation)?;
+
+        self.fee_vault.counters.record_withdrawal(amount)?;
+
+        emit!(FeesWithdrawn {
+            admin: self.admin.key(),
+            destination: self.destination.key(),
+            amount,
+            total_withdrawn: self.fee_vault.counters.total_withdrawn,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
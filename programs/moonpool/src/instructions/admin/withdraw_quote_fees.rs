@@ -0,0 +1,83 @@
+use crate::errors::ErrorCode;
+use crate::events::QuoteFeesWithdrawn;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct WithdrawQuoteFees<'info> {
+    #[account(
+        seeds = [FEE_VAULT_SEED],
+        bump,
+        constraint = fee_vault.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(
+        mut,
+        seeds = [FEE_LEDGER_SEED, quote_mint.key().as_ref()],
+        bump = fee_ledger.bump,
+    )]
+    pub fee_ledger: Box<Account<'info, FeeLedger>>,
+
+    #[account(
+        mut,
+        address = fee_ledger.vault,
+    )]
+    pub fee_ledger_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = quote_mint,
+    )]
+    pub destination: Box<Account<'info, TokenAccount>>,
+
+    pub quote_mint: Box<Account<'info, Mint>>,
+
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> WithdrawQuoteFees<'info> {
+    pub fn handler(&mut self, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+
+        if amount > self.fee_ledger_vault.amount {
+            return Err(ErrorCode::InsufficientFeeBalance.into());
+        }
+
+        let quote_mint_key = self.quote_mint.key();
+        let fee_ledger_seeds = &[
+            FEE_LEDGER_SEED,
+            quote_mint_key.as_ref(),
+            &[self.fee_ledger.bump],
+        ];
+        let fee_ledger_signer = &[&fee_ledger_seeds[..]];
+
+        let withdraw_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.fee_ledger_vault.to_account_info(),
+                to: self.destination.to_account_info(),
+                authority: self.fee_ledger.to_account_info(),
+            },
+            fee_ledger_signer,
+        );
+        transfer(withdraw_context, amount)?;
+
+        self.fee_ledger.counters.record_withdrawal(amount)?;
+
+        emit!(QuoteFeesWithdrawn {
+            admin: self.admin.key(),
+            mint: quote_mint_key,
+            destination: self.destination.key(),
+            amount,
+            total_withdrawn: self.fee_ledger.counters.total_withdrawn,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
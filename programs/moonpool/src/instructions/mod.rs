@@ -1,14 +1,35 @@
 pub mod admin;
 pub mod pool;
 pub mod raydium;
+pub use admin::accept_admin::*;
+pub use admin::cancel_admin_proposal::*;
 pub use admin::initialize::*;
+pub use admin::propose_admin::*;
+pub use admin::set_paused::*;
+pub use admin::update_config::*;
+pub use admin::withdraw_fees::*;
+pub use admin::withdraw_quote_fees::*;
 
 pub use pool::add_asset::*;
+pub use pool::add_liquidity::*;
 pub use pool::buy_droplets::*;
+pub use pool::claim_refund::*;
+pub use pool::close_asset::*;
+pub use pool::close_member::*;
+pub use pool::close_pool::*;
+pub use pool::close_pool_account::*;
 pub use pool::contribute::*;
 pub use pool::create_pool::*;
 pub use pool::create_pool_mint::*;
+pub use pool::finalize_raise::*;
+pub use pool::mature_pool::*;
+pub use pool::quote::*;
+pub use pool::redeem::*;
 pub use pool::rescind_contribution::*;
 pub use pool::sell_droplets::*;
+pub use pool::set_allowlist::*;
+pub use pool::set_pool_halted::*;
+pub use pool::settle_pool::*;
+pub use pool::withdraw_liquidity::*;
 pub use raydium::initialize_lp::*;
 pub use raydium::swap::*;
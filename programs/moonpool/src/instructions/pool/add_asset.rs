@@ -54,7 +54,11 @@ pub struct AddAsset<'info> {
 }
 
 impl<'info> AddAsset<'info> {
-    pub fn handler(&mut self, amount: u64) -> Result<()> {
+    pub fn handler(&mut self, amount: u64, asset_bump: u8) -> Result<()> {
+        self.pool.ensure_trading_open(&self.moonpool)?;
+        self.pool
+            .ensure_status(&[PoolStatus::Created, PoolStatus::Raising, PoolStatus::Active])?;
+
         if self.pool.maturity_date < Clock::get()?.unix_timestamp {
             return Err(ErrorCode::PoolMatured.into());
         }
@@ -74,6 +78,13 @@ impl<'info> AddAsset<'info> {
         self.asset.mint = self.mint.key();
         self.asset.vault = self.asset_vault.key();
         self.asset.amount = amount;
+        self.asset.bump = asset_bump;
+
+        self.pool.asset_count = self
+            .pool
+            .asset_count
+            .checked_add(1)
+            .ok_or(ErrorCode::InvalidCalculation)?;
         Ok(())
     }
 }
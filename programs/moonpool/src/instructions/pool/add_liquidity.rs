@@ -0,0 +1,103 @@
+use crate::errors::ErrorCode;
+use crate::events::LiquidityAdded;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{sync_native, transfer, Mint, SyncNative, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct AddLiquidity<'info> {
+    #[account(
+        mut,
+        constraint = pool.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [POOL_QUOTE_VAULT_SEED, pool.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = pool,
+    )]
+    pub pool_quote_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = quote_mint,
+        associated_token::authority = owner,
+    )]
+    pub owner_quote_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        constraint = quote_mint.key() == pool.quote_mint,
+    )]
+    pub quote_mint: Box<Account<'info, Mint>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AddLiquidity<'info> {
+    /// Deposits quote into the pool vault without minting droplets. Liquidity is fixed once
+    /// trading opens so the curve never moves under open positions. The deposit isn't paid
+    /// out to holders and comes back through `withdraw_liquidity`.
+    pub fn handler(&mut self, amount: u64) -> Result<()> {
+        self.pool
+            .ensure_status(&[PoolStatus::Created, PoolStatus::Raising])?;
+
+        if amount == 0 {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+
+        // SOL pools take lamports, wrapped here so owners don't need to hold WSOL
+        if self.pool.quote_mint == NATIVE_SOL_SPL_MINT {
+            let wrap_ix = solana_program::system_instruction::transfer(
+                &self.owner.key(),
+                &self.owner_quote_token_account.key(),
+                amount,
+            );
+            solana_program::program::invoke(
+                &wrap_ix,
+                &[
+                    self.owner.to_account_info(),
+                    self.owner_quote_token_account.to_account_info(),
+                    self.system_program.to_account_info(),
+                ],
+            )?;
+            sync_native(CpiContext::new(
+                self.token_program.to_account_info(),
+                SyncNative {
+                    account: self.owner_quote_token_account.to_account_info(),
+                },
+            ))?;
+        }
+
+        let transfer_context = CpiContext::new(
+            self.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: self.owner_quote_token_account.to_account_info(),
+                to: self.pool_quote_vault.to_account_info(),
+                authority: self.owner.to_account_info(),
+            },
+        );
+        transfer(transfer_context, amount)?;
+
+        self.pool.record_owner_liquidity(amount)?;
+
+        emit!(LiquidityAdded {
+            pool: self.pool.key(),
+            owner: self.owner.key(),
+            amount,
+            droplet_liquidity: self.pool.droplet_liquidity,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
@@ -3,7 +3,7 @@ use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{mint_to, Mint, Token, TokenAccount};
+use anchor_spl::token::{mint_to, sync_native, transfer, Mint, SyncNative, Token, TokenAccount};
 
 #[derive(Accounts)]
 pub struct BuyDroplets<'info> {
@@ -16,25 +16,40 @@ pub struct BuyDroplets<'info> {
 
     #[account(
         mut,
-        seeds = [FEE_VAULT_SEED],
-        bump,
+        seeds = [FEE_LEDGER_SEED, quote_mint.key().as_ref()],
+        bump = fee_ledger.bump,
+    )]
+    pub fee_ledger: Box<Account<'info, FeeLedger>>,
+
+    #[account(
+        mut,
+        address = fee_ledger.vault,
+    )]
+    pub fee_ledger_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
     )]
-    pub fee_vault: Box<Account<'info, FeeVault>>,
+    pub config: Box<Account<'info, ProtocolConfig>>,
 
     #[account(mut)]
     pub pool: Box<Account<'info, Pool>>,
 
     #[account(
         mut,
-        seeds = [POOL_WSOL_VAULT_SEED, pool.key().as_ref()],
+        seeds = [POOL_QUOTE_VAULT_SEED, pool.key().as_ref()],
         bump,
+        token::mint = quote_mint,
+        token::authority = pool,
     )]
-    pub pool_wsol_vault: Box<Account<'info, TokenAccount>>,
+    pub pool_quote_vault: Box<Account<'info, TokenAccount>>,
 
     #[account(
         mut,
-        seeds = [DROPLET_MINT_SEED, pool.key().as_ref(), pool.name.as_bytes()],
+        seeds = [DROPLET_MINT_SEED, pool.key().as_ref()],
         bump,
+        constraint = droplet_mint.key() == pool.droplet_mint,
     )]
     pub droplet_mint: Box<Account<'info, Mint>>,
 
@@ -44,6 +59,22 @@ pub struct BuyDroplets<'info> {
     )]
     pub pool_owner: SystemAccount<'info>,
 
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = quote_mint,
+        associated_token::authority = pool_owner,
+    )]
+    pub pool_owner_quote_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = quote_mint,
+        associated_token::authority = payer,
+    )]
+    pub payer_quote_token_account: Box<Account<'info, TokenAccount>>,
+
     #[account(
         init_if_needed,
         payer = payer,
@@ -55,72 +86,133 @@ pub struct BuyDroplets<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    #[account(
+        constraint = quote_mint.key() == pool.quote_mint,
+    )]
+    pub quote_mint: Box<Account<'info, Mint>>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> BuyDroplets<'info> {
-    pub fn handler(&mut self, amount: u64) -> Result<()> {
-        let current_time = Clock::get()?.unix_timestamp;
+    /// `max_cost` covers the droplet cost and both fees, in quote base units.
+    pub fn handler(&mut self, amount: u64, max_cost: u64, deadline: Option<i64>) -> Result<()> {
+        self.ensure_can_buy(deadline)?;
 
-        if current_time < self.pool.raise_period_end {
-            return Err(ErrorCode::RaisePeriodNotEnded.into());
+        if amount == 0 {
+            return Err(ErrorCode::InvalidAmount.into());
         }
 
-        if current_time > self.pool.maturity_date {
-            return Err(ErrorCode::PoolMatured.into());
+        let quote = self
+            .pool
+            .quote_buy(&self.config, amount, self.pool_quote_vault.amount)?;
+        if quote.settlement > max_cost {
+            return Err(ErrorCode::SlippageExceeded.into());
         }
 
-        if amount == 0 {
+        self.execute(&quote)
+    }
+
+    /// Spends at most `quote_amount`, fees included, on as many droplets as it buys.
+    pub fn handler_exact_in(
+        &mut self,
+        quote_amount: u64,
+        min_droplets_out: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        self.ensure_can_buy(deadline)?;
+
+        if quote_amount == 0 {
             return Err(ErrorCode::InvalidAmount.into());
         }
 
-        let cost = self.pool.get_buy_price(amount)?;
-        let pool_owner_fee = cost * POOL_OWNER_FEE / 100;
-        let program_fee = cost * PROGRAM_FEE / 100;
+        let quote = self.pool.quote_buy_exact_in(
+            &self.config,
+            quote_amount,
+            self.pool_quote_vault.amount,
+        )?;
+        if quote.droplets < min_droplets_out {
+            return Err(ErrorCode::SlippageExceeded.into());
+        }
+
+        self.execute(&quote)
+    }
+
+    fn ensure_can_buy(&self, deadline: Option<i64>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+
+        Pool::ensure_before_deadline(deadline, current_time)?;
+
+        self.pool.ensure_trading_open(&self.moonpool)?;
+
+        self.pool.ensure_status(&[PoolStatus::Active])?;
+
+        if current_time > self.pool.maturity_date {
+            return Err(ErrorCode::PoolMatured.into());
+        }
+
+        Ok(())
+    }
 
-        // Transfer the cost in SOL from the payer to the pool's vault
-        let pool_vault_ix = solana_program::system_instruction::transfer(
-            &self.payer.key(),
-            &self.pool_wsol_vault.key(),
-            cost,
+    fn execute(&mut self, quote: &TradeQuote) -> Result<()> {
+        // SOL pools take lamports, wrapped here so buyers don't need to hold WSOL
+        if self.pool.quote_mint == NATIVE_SOL_SPL_MINT {
+            let wrap_ix = solana_program::system_instruction::transfer(
+                &self.payer.key(),
+                &self.payer_quote_token_account.key(),
+                quote.settlement,
+            );
+            solana_program::program::invoke(
+                &wrap_ix,
+                &[
+                    self.payer.to_account_info(),
+                    self.payer_quote_token_account.to_account_info(),
+                    self.system_program.to_account_info(),
+                ],
+            )?;
+            sync_native(CpiContext::new(
+                self.token_program.to_account_info(),
+                SyncNative {
+                    account: self.payer_quote_token_account.to_account_info(),
+                },
+            ))?;
+        }
+
+        // Transfer the cost from the payer to the pool's vault
+        let cost_context = CpiContext::new(
+            self.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: self.payer_quote_token_account.to_account_info(),
+                to: self.pool_quote_vault.to_account_info(),
+                authority: self.payer.to_account_info(),
+            },
         );
-        solana_program::program::invoke(
-            &pool_vault_ix,
-            &[
-                self.payer.to_account_info(),
-                self.pool_wsol_vault.to_account_info(),
-            ],
-        )?;
+        transfer(cost_context, quote.value)?;
 
-        // Transfer the pool owner fee in SOL from the payer to the pool owner
-        let pool_owner_ix = solana_program::system_instruction::transfer(
-            &self.payer.key(),
-            &self.pool.owner,
-            pool_owner_fee,
+        // Transfer the pool owner fee from the payer to the pool owner
+        let pool_owner_context = CpiContext::new(
+            self.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: self.payer_quote_token_account.to_account_info(),
+                to: self.pool_owner_quote_token_account.to_account_info(),
+                authority: self.payer.to_account_info(),
+            },
         );
-        solana_program::program::invoke(
-            &pool_owner_ix,
-            &[
-                self.payer.to_account_info(),
-                self.pool_owner.to_account_info(),
-            ],
-        )?;
+        transfer(pool_owner_context, quote.pool_owner_fee)?;
 
-        // Transfer the program fee in SOL from the payer to the fee vault
-        let fee_vault_ix = solana_program::system_instruction::transfer(
-            &self.payer.key(),
-            &self.fee_vault.key(),
-            program_fee,
+        // Transfer the program fee from the payer to the mint's fee ledger
+        let fee_context = CpiContext::new(
+            self.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: self.payer_quote_token_account.to_account_info(),
+                to: self.fee_ledger_vault.to_account_info(),
+                authority: self.payer.to_account_info(),
+            },
         );
-        solana_program::program::invoke(
-            &fee_vault_ix,
-            &[
-                self.payer.to_account_info(),
-                self.fee_vault.to_account_info(),
-            ],
-        )?;
+        transfer(fee_context, quote.program_fee)?;
+        self.fee_ledger
+            .record_fee(FeeSource::Buy, quote.program_fee)?;
 
         // Mint droplets to payer's token account
         let pool_owner_key = self.pool.owner.key();
@@ -141,8 +233,8 @@ impl<'info> BuyDroplets<'info> {
             },
             pool_signer,
         );
-        mint_to(cpi_context, amount)?;
-        self.pool.droplet_supply += amount;
+        mint_to(cpi_context, quote.droplets)?;
+        self.pool.droplet_supply += quote.droplets;
 
         Ok(())
     }
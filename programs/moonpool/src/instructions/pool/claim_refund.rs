@@ -0,0 +1,140 @@
+use crate::errors::ErrorCode;
+use crate::events::RefundClaimed;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{burn, transfer, Burn, Mint, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct ClaimRefund<'info> {
+    #[account(
+        seeds = [MOONPOOL_SEED],
+        bump,
+    )]
+    pub moonpool: Box<Account<'info, Moonpool>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, ProtocolConfig>>,
+
+    #[account(mut)]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [POOL_QUOTE_VAULT_SEED, pool.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = pool,
+    )]
+    pub pool_quote_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [DROPLET_MINT_SEED, pool.key().as_ref()],
+        bump,
+        constraint = droplet_mint.key() == pool.droplet_mint,
+    )]
+    pub droplet_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = droplet_mint,
+        associated_token::authority = payer,
+    )]
+    pub payer_droplet_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = quote_mint,
+        associated_token::authority = payer,
+    )]
+    pub payer_quote_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Absent when the droplets were received by transfer rather than contributed
+    #[account(
+        mut,
+        seeds = [MEMBER_SEED, pool.key().as_ref(), payer.key().as_ref()],
+        bump = member.bump,
+    )]
+    pub member: Option<Box<Account<'info, Member>>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        constraint = quote_mint.key() == pool.quote_mint,
+    )]
+    pub quote_mint: Box<Account<'info, Mint>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimRefund<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        self.pool.ensure_exits_open(&self.moonpool, &self.config)?;
+
+        self.pool.ensure_status(&[PoolStatus::Failed])?;
+
+        let droplets = self.payer_droplet_token_account.amount;
+        let refund_amount = self.pool.pro_rata_share(
+            self.pool.holder_balance(self.pool_quote_vault.amount),
+            droplets,
+        )?;
+
+        // Burn all of the contributor's droplets
+        let burn_context = CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.droplet_mint.to_account_info(),
+                from: self.payer_droplet_token_account.to_account_info(),
+                authority: self.payer.to_account_info(),
+            },
+        );
+        burn(burn_context, droplets)?;
+
+        // Return the contributor's share of the raised quote tokens
+        let pool_owner_key = self.pool.owner.key();
+        let pool_seeds = &[
+            POOL_SEED,
+            pool_owner_key.as_ref(),
+            self.pool.name.as_bytes(),
+            &[self.pool.bump],
+        ];
+        let pool_signer = &[&pool_seeds[..]];
+
+        let refund_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.pool_quote_vault.to_account_info(),
+                to: self.payer_quote_token_account.to_account_info(),
+                authority: self.pool.to_account_info(),
+            },
+            pool_signer,
+        );
+        transfer(refund_context, refund_amount)?;
+
+        self.pool.droplet_supply = self
+            .pool
+            .droplet_supply
+            .checked_sub(droplets)
+            .ok_or(ErrorCode::InvalidCalculation)?;
+
+        if let Some(member) = self.member.as_mut() {
+            member.record_refund(refund_amount)?;
+        }
+
+        emit!(RefundClaimed {
+            pool: self.pool.key(),
+            contributor: self.payer.key(),
+            droplets_burned: droplets,
+            refund_amount,
+        });
+
+        Ok(())
+    }
+}
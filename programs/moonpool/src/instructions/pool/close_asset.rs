@@ -0,0 +1,114 @@
+use crate::errors::ErrorCode;
+use crate::events::AssetClosed;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{close_account, transfer, CloseAccount, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct CloseAsset<'info> {
+    #[account(mut)]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        constraint = pool.owner == pool_owner.key(),
+    )]
+    pub pool_owner: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [ASSET_SEED, pool.key().as_ref(), asset.mint.as_ref()],
+        bump = asset.bump,
+        constraint = asset.pool == pool.key() @ ErrorCode::InvalidAssetAccounts,
+        close = pool_owner,
+    )]
+    pub asset: Box<Account<'info, Asset>>,
+
+    #[account(
+        mut,
+        address = asset.vault,
+    )]
+    pub asset_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Receives any dust left in the vault, required only when there is some
+    #[account(
+        mut,
+        token::mint = asset.mint,
+        token::authority = pool_owner,
+    )]
+    pub owner_asset_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Droplets in it don't count as outstanding, pass it once the pool mint exists
+    #[account(
+        seeds = [POOL_DROPLET_VAULT_SEED, pool.key().as_ref()],
+        bump,
+    )]
+    pub pool_droplet_vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> CloseAsset<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        let stray_droplets = self
+            .pool_droplet_vault
+            .as_ref()
+            .map_or(0, |vault| vault.amount);
+        self.pool.ensure_closable(stray_droplets)?;
+
+        let pool_key = self.pool.key();
+        let asset_seeds = &[
+            ASSET_SEED,
+            pool_key.as_ref(),
+            self.asset.mint.as_ref(),
+            &[self.asset.bump],
+        ];
+        let asset_signer = &[&asset_seeds[..]];
+
+        // Dust can't hold the asset open, it goes to the pool owner
+        let dust = self.asset_vault.amount;
+        if dust != 0 {
+            let owner_asset_token_account = self
+                .owner_asset_token_account
+                .as_ref()
+                .ok_or(ErrorCode::VaultNotEmpty)?;
+            let sweep_context = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.asset_vault.to_account_info(),
+                    to: owner_asset_token_account.to_account_info(),
+                    authority: self.asset.to_account_info(),
+                },
+                asset_signer,
+            );
+            transfer(sweep_context, dust)?;
+        }
+
+        // Close the asset vault and return its rent to the pool owner
+
+        let close_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.asset_vault.to_account_info(),
+                destination: self.pool_owner.to_account_info(),
+                authority: self.asset.to_account_info(),
+            },
+            asset_signer,
+        );
+        close_account(close_context)?;
+
+        self.pool.asset_count = self
+            .pool
+            .asset_count
+            .checked_sub(1)
+            .ok_or(ErrorCode::InvalidCalculation)?;
+
+        emit!(AssetClosed {
+            pool: pool_key,
+            asset: self.asset.key(),
+            mint: self.asset.mint,
+        });
+
+        Ok(())
+    }
+}
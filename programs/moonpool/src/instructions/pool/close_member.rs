@@ -0,0 +1,68 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::TokenAccount;
+
+#[derive(Accounts)]
+pub struct CloseMember<'info> {
+    #[account(
+        mut,
+        seeds = [MEMBER_SEED, member.pool.as_ref(), user.key().as_ref()],
+        bump = member.bump,
+        close = user,
+    )]
+    pub member: Box<Account<'info, Member>>,
+
+    /// CHECK: The pool may already have been closed, it is deserialized in the handler otherwise
+    #[account(address = member.pool)]
+    pub pool: UncheckedAccount<'info>,
+
+    /// Required while the pool account still exists
+    #[account(token::authority = user)]
+    pub user_droplet_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+impl<'info> CloseMember<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        // A closed pool has no droplets outstanding, so its ledger can always go, whether
+        // or not the pool account has been reclaimed yet
+        if self.pool.data_is_empty() {
+            return Ok(());
+        }
+
+        if *self.pool.owner != crate::ID {
+            return Err(ErrorCode::InvalidAccount.into());
+        }
+        let pool = Pool::try_deserialize(&mut &self.pool.try_borrow_data()?[..])?;
+        if pool.status == PoolStatus::Closed {
+            return Ok(());
+        }
+
+        // The ledger backs rescinds and wallet caps until the raise is over
+        pool.ensure_status(&[
+            PoolStatus::Active,
+            PoolStatus::Matured,
+            PoolStatus::Settled,
+            PoolStatus::Failed,
+        ])?;
+
+        let droplet_token_account = self
+            .user_droplet_token_account
+            .as_ref()
+            .ok_or(ErrorCode::InvalidAccount)?;
+        if droplet_token_account.key()
+            != get_associated_token_address(&self.user.key(), &pool.droplet_mint)
+        {
+            return Err(ErrorCode::InvalidAccount.into());
+        }
+        if droplet_token_account.amount != 0 {
+            return Err(ErrorCode::MemberBalanceNotZero.into());
+        }
+
+        Ok(())
+    }
+}
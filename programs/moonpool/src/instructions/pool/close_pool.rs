@@ -0,0 +1,165 @@
+use crate::errors::ErrorCode;
+use crate::events::PoolStatusChanged;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token::instruction::AuthorityType;
+use anchor_spl::token::{
+    burn, close_account, set_authority, transfer, Burn, CloseAccount, Mint, SetAuthority, Token,
+    TokenAccount, Transfer,
+};
+
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+    #[account(mut)]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        constraint = pool.owner == pool_owner.key(),
+    )]
+    pub pool_owner: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [POOL_QUOTE_VAULT_SEED, pool.key().as_ref()],
+        bump,
+    )]
+    pub pool_quote_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Receives any quote dust left in the vault, required only when there is some
+    #[account(
+        mut,
+        token::mint = pool.quote_mint,
+        token::authority = pool_owner,
+    )]
+    pub owner_quote_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Required once the pool mint has been created
+    #[account(
+        mut,
+        seeds = [POOL_DROPLET_VAULT_SEED, pool.key().as_ref()],
+        bump,
+    )]
+    pub pool_droplet_vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Required once the pool mint has been created
+    #[account(
+        mut,
+        seeds = [DROPLET_MINT_SEED, pool.key().as_ref()],
+        bump,
+        constraint = droplet_mint.key() == pool.droplet_mint,
+    )]
+    pub droplet_mint: Option<Box<Account<'info, Mint>>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> ClosePool<'info> {
+    /// Winds down the pool's token accounts and marks it Closed. The pool account itself
+    /// stays readable until the owner reclaims it with `close_pool_account`.
+    pub fn handler(&mut self) -> Result<()> {
+        // A pool that failed before create_pool_mint has no droplet vault or mint
+        let minted = self.pool.droplet_mint != Pubkey::default();
+        let droplet_accounts = match (&self.pool_droplet_vault, &self.droplet_mint) {
+            (Some(vault), Some(mint)) if minted => Some((vault, mint)),
+            (None, None) if !minted => None,
+            _ => return Err(ErrorCode::InvalidAccount.into()),
+        };
+        let stray_droplets = droplet_accounts.map_or(0, |(vault, _)| vault.amount);
+        self.pool.ensure_closable(stray_droplets)?;
+
+        if self.pool.asset_count != 0 {
+            return Err(ErrorCode::PoolHasAssets.into());
+        }
+
+        let pool_owner_key = self.pool.owner.key();
+        let pool_seeds = &[
+            POOL_SEED,
+            pool_owner_key.as_ref(),
+            self.pool.name.as_bytes(),
+            &[self.pool.bump],
+        ];
+        let pool_signer = &[&pool_seeds[..]];
+
+        // Dust can't hold the pool open: quote goes to the owner, stray droplets are burned
+        let quote_dust = self.pool_quote_vault.amount;
+        if quote_dust != 0 {
+            let owner_quote_token_account = self
+                .owner_quote_token_account
+                .as_ref()
+                .ok_or(ErrorCode::VaultNotEmpty)?;
+            let sweep_context = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.pool_quote_vault.to_account_info(),
+                    to: owner_quote_token_account.to_account_info(),
+                    authority: self.pool.to_account_info(),
+                },
+                pool_signer,
+            );
+            transfer(sweep_context, quote_dust)?;
+        }
+
+        if let Some((droplet_vault, droplet_mint)) = droplet_accounts {
+            if stray_droplets != 0 {
+                let burn_context = CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    Burn {
+                        mint: droplet_mint.to_account_info(),
+                        from: droplet_vault.to_account_info(),
+                        authority: self.pool.to_account_info(),
+                    },
+                    pool_signer,
+                );
+                burn(burn_context, stray_droplets)?;
+            }
+        }
+
+        // Close the pool vaults and return their rent to the pool owner
+        let mut vaults = vec![self.pool_quote_vault.to_account_info()];
+        if let Some((droplet_vault, _)) = droplet_accounts {
+            vaults.push(droplet_vault.to_account_info());
+        }
+        for vault in vaults {
+            let close_context = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                CloseAccount {
+                    account: vault,
+                    destination: self.pool_owner.to_account_info(),
+                    authority: self.pool.to_account_info(),
+                },
+                pool_signer,
+            );
+            close_account(close_context)?;
+        }
+
+        // Revoke the pool's mint authority so no droplets can be minted after closing
+        if let Some((_, droplet_mint)) = droplet_accounts {
+            let authority_context = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                SetAuthority {
+                    current_authority: self.pool.to_account_info(),
+                    account_or_mint: droplet_mint.to_account_info(),
+                },
+                pool_signer,
+            );
+            set_authority(authority_context, AuthorityType::MintTokens, None)?;
+        }
+
+        self.pool.droplet_supply = self
+            .pool
+            .droplet_supply
+            .checked_sub(stray_droplets)
+            .ok_or(ErrorCode::InvalidCalculation)?;
+        let previous_status = self.pool.transition(PoolStatus::Closed)?;
+
+        emit!(PoolStatusChanged {
+            pool: self.pool.key(),
+            previous_status,
+            status: self.pool.status,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ClosePoolAccount<'info> {
+    #[account(
+        mut,
+        close = pool_owner,
+        constraint = pool.owner == pool_owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(mut)]
+    pub pool_owner: Signer<'info>,
+}
+
+impl<'info> ClosePoolAccount<'info> {
+    /// Returns the pool account's rent to its owner once `close_pool` has wound it down.
+    pub fn handler(&mut self) -> Result<()> {
+        self.pool.ensure_status(&[PoolStatus::Closed])
+    }
+}
@@ -1,10 +1,21 @@
 use crate::errors::ErrorCode;
+use crate::events::Contributed;
+use crate::fees::FeeMode;
+use crate::merkle;
 use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{mint_to, sync_native, transfer, Mint, SyncNative, Token, TokenAccount};
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AllowlistProof {
+    // Per-address allocation committed to in the leaf, 0 when uncapped. Pools with a
+    // guaranteed phase reserve it for the address, others cap FCFS contributions with it.
+    pub allocation: u64,
+    pub proof: Vec<[u8; 32]>,
+}
+
 #[derive(Accounts)]
 pub struct Contribute<'info> {
     #[account(
@@ -16,10 +27,22 @@ pub struct Contribute<'info> {
 
     #[account(
         mut,
-        seeds = [FEE_VAULT_SEED],
-        bump,
+        seeds = [FEE_LEDGER_SEED, quote_mint.key().as_ref()],
+        bump = fee_ledger.bump,
+    )]
+    pub fee_ledger: Box<Account<'info, FeeLedger>>,
+
+    #[account(
+        mut,
+        address = fee_ledger.vault,
     )]
-    pub fee_vault: Box<Account<'info, FeeVault>>,
+    pub fee_ledger_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, ProtocolConfig>>,
 
     #[account(
         mut,
@@ -37,20 +60,28 @@ pub struct Contribute<'info> {
 
     #[account(
         mut,
-        seeds = [POOL_WSOL_VAULT_SEED, pool.key().as_ref()],
+        seeds = [POOL_QUOTE_VAULT_SEED, pool.key().as_ref()],
         bump,
-        token::mint = wsol_mint,
+        token::mint = quote_mint,
         token::authority = pool,
     )]
-    pub pool_wsol_vault: Box<Account<'info, TokenAccount>>,
+    pub pool_quote_vault: Box<Account<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
         payer = payer,
-        associated_token::mint = wsol_mint,
+        associated_token::mint = quote_mint,
         associated_token::authority = payer,
     )]
-    pub payer_wsol_token_account: Box<Account<'info, TokenAccount>>,
+    pub payer_quote_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = quote_mint,
+        associated_token::authority = pool_owner,
+    )]
+    pub pool_owner_quote_token_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
@@ -60,6 +91,15 @@ pub struct Contribute<'info> {
     )]
     pub payer_droplet_token_account: Box<Account<'info, TokenAccount>>,
 
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + Member::INIT_SPACE,
+        seeds = [MEMBER_SEED, pool.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub member: Box<Account<'info, Member>>,
+
     #[account(
         mut,
         constraint = droplet_mint.key() == pool.droplet_mint,
@@ -68,17 +108,30 @@ pub struct Contribute<'info> {
 
     #[account(mut)]
     pub payer: Signer<'info>,
-    pub wsol_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        constraint = quote_mint.key() == pool.quote_mint,
+    )]
+    pub quote_mint: Box<Account<'info, Mint>>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> Contribute<'info> {
-    pub fn handler(&mut self, amount: u64) -> Result<()> {
+    pub fn handler(
+        &mut self,
+        amount: u64,
+        allowlist_proof: Option<AllowlistProof>,
+        member_bump: u8,
+    ) -> Result<()> {
         let current_time = Clock::get()?.unix_timestamp;
 
-        if current_time > self.pool.raise_period_end {
+        self.pool.ensure_trading_open(&self.moonpool)?;
+        self.pool.ensure_status(&[PoolStatus::Raising])?;
+
+        if current_time < self.pool.raise_period_start || current_time > self.pool.raise_period_end
+        {
             return Err(ErrorCode::PoolNotInRaisePeriod.into());
         }
 
@@ -86,55 +139,117 @@ impl<'info> Contribute<'info> {
             return Err(ErrorCode::InvalidAmount.into());
         }
 
-        let amount_to_mint = self.pool.calculate_sol_to_droplets(amount)?;
-        self.pool.validate(amount_to_mint)?;
+        if self.pool.is_filled {
+            return Err(ErrorCode::PoolFilled.into());
+        }
 
-        // Transfer the program fee in SOL from the payer to the fee vault
-        let program_fee = amount as f64 * (PROGRAM_FEE as f64 / 100.0);
-        let fee_vault_ix = solana_program::system_instruction::transfer(
-            &self.payer.key(),
-            &self.fee_vault.key(),
-            program_fee as u64,
-        );
-        solana_program::program::invoke(
-            &fee_vault_ix,
-            &[
-                self.payer.to_account_info(),
-                self.fee_vault.to_account_info(),
-            ],
-        )?;
+        let (phase_kind, phase) = self
+            .pool
+            .active_phase(current_time)
+            .ok_or(ErrorCode::PoolNotInRaisePeriod)?;
+
+        // Public contributions can still carry a proof to claim a guarantee
+        let allocation = match allowlist_proof {
+            Some(allowlist_proof) => {
+                let leaf = merkle::allowlist_leaf(&self.payer.key(), allowlist_proof.allocation);
+                if !merkle::verify(&allowlist_proof.proof, self.pool.allowlist_root, leaf) {
+                    return Err(ErrorCode::InvalidAllowlistProof.into());
+                }
+                Some(allowlist_proof.allocation)
+            }
+            None if phase_kind.requires_allowlist() => {
+                return Err(ErrorCode::AllowlistProofRequired.into());
+            }
+            None => None,
+        };
+        let guarantees = self.pool.has_guarantees();
+        let guarantee = match allocation {
+            Some(allocation) if guarantees => self.member.guarantee_left(allocation),
+            _ => 0,
+        };
+
+        let quote = self
+            .pool
+            .quote_contribute(&self.config, amount, &phase, guarantee)?;
+        let accepted_amount = quote.value;
+        let amount_to_mint = quote.droplets;
+        let program_fee = quote.program_fee;
 
-        // Convert the user's contribution amount from SOL to WSOL
-        let wrap_ix = solana_program::system_instruction::transfer(
-            &self.payer.key(),
-            &self.payer_wsol_token_account.key(),
+        if let Some(allocation) = allocation {
+            let guaranteed = self.member.claim_allocation(
+                phase_kind,
+                accepted_amount,
+                allocation,
+                guarantees,
+            )?;
+            self.pool.record_guaranteed(guaranteed)?;
+        }
+
+        self.pool.ensure_contribution_limits(
+            &phase,
             amount,
-        );
-        solana_program::program::invoke(
-            &wrap_ix,
-            &[
-                self.payer.to_account_info(),
-                self.payer_wsol_token_account.to_account_info(),
-                self.system_program.to_account_info(),
-            ],
+            accepted_amount,
+            self.member.net_contribution(),
+            self.member.phase_contributed[phase_kind as usize],
         )?;
-        let _sync_native_ix = sync_native(CpiContext::new(
+
+        // SOL pools take lamports, wrapped here so contributors don't need to hold WSOL
+        if self.pool.quote_mint == NATIVE_SOL_SPL_MINT {
+            let wrap_ix = solana_program::system_instruction::transfer(
+                &self.payer.key(),
+                &self.payer_quote_token_account.key(),
+                quote.settlement,
+            );
+            solana_program::program::invoke(
+                &wrap_ix,
+                &[
+                    self.payer.to_account_info(),
+                    self.payer_quote_token_account.to_account_info(),
+                    self.system_program.to_account_info(),
+                ],
+            )?;
+            sync_native(CpiContext::new(
+                self.token_program.to_account_info(),
+                SyncNative {
+                    account: self.payer_quote_token_account.to_account_info(),
+                },
+            ))?;
+        }
+
+        // Transfer the pool owner fee from the payer to the pool owner
+        let pool_owner_context = CpiContext::new(
             self.token_program.to_account_info(),
-            SyncNative {
-                account: self.payer_wsol_token_account.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: self.payer_quote_token_account.to_account_info(),
+                to: self.pool_owner_quote_token_account.to_account_info(),
+                authority: self.payer.to_account_info(),
             },
-        ))?;
+        );
+        transfer(pool_owner_context, quote.pool_owner_fee)?;
 
-        // Transfer the converted amount of WSOL to the pool vault
+        // Transfer the program fee from the payer to the mint's fee ledger
+        let fee_context = CpiContext::new(
+            self.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: self.payer_quote_token_account.to_account_info(),
+                to: self.fee_ledger_vault.to_account_info(),
+                authority: self.payer.to_account_info(),
+            },
+        );
+        transfer(fee_context, program_fee)?;
+        self.fee_ledger
+            .record_fee(FeeSource::Contribute, program_fee)?;
+
+        // Transfer the contribution to the pool vault
         let transfer_ix = CpiContext::new(
             self.token_program.to_account_info(),
             anchor_spl::token::Transfer {
-                from: self.payer_wsol_token_account.to_account_info(),
-                to: self.pool_wsol_vault.to_account_info(),
+                from: self.payer_quote_token_account.to_account_info(),
+                to: self.pool_quote_vault.to_account_info(),
                 authority: self.payer.to_account_info(),
             },
         );
-        transfer(transfer_ix, amount)?;
+        transfer(transfer_ix, accepted_amount)?;
 
         // Mint proportionate droplets to payer's token account
         let pool_owner_key = self.pool.owner.key();
@@ -166,9 +281,44 @@ impl<'info> Contribute<'info> {
         self.pool.total_raised = self
             .pool
             .total_raised
-            .checked_add(amount)
+            .checked_add(accepted_amount)
             .ok_or_else(|| ErrorCode::InvalidAmount)?;
 
+        self.pool.record_liquidity(accepted_amount)?;
+
+        if self.pool.total_raised >= self.pool.raise_goal {
+            self.pool.is_filled = true;
+        }
+
+        if self.member.first_contribution_at == 0 {
+            self.member.pool = self.pool.key();
+            self.member.user = self.payer.key();
+            self.member.bump = member_bump;
+        }
+        self.member.record_contribution(
+            phase_kind,
+            accepted_amount,
+            amount_to_mint,
+            current_time,
+        )?;
+
+        // Exclusive fees were charged on top of `amount`, inclusive ones out of it
+        let refunded_amount = match self.config.fee_mode {
+            FeeMode::Exclusive => amount.saturating_sub(accepted_amount),
+            FeeMode::Inclusive => amount.saturating_sub(quote.settlement),
+        };
+
+        emit!(Contributed {
+            pool: self.pool.key(),
+            contributor: self.payer.key(),
+            requested_amount: amount,
+            accepted_amount,
+            refunded_amount,
+            droplets_minted: amount_to_mint,
+            total_raised: self.pool.total_raised,
+            is_filled: self.pool.is_filled,
+        });
+
         Ok(())
     }
 }
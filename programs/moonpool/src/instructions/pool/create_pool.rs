@@ -1,8 +1,29 @@
+use crate::curve::CurveKind;
 use crate::errors::ErrorCode;
 use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CreatePoolParams {
+    pub symbol: String,
+    pub raise_goal: u64,
+    // Soft cap; a raise that ends below it fails and contributors are refunded
+    pub min_raise: u64,
+    // A start at or before the current time opens the raise immediately
+    pub raise_start: i64,
+    pub raise_duration: i64,
+    pub maturity_date: i64,
+    pub min_contribution: Option<u64>,
+    pub max_contribution_per_wallet: Option<u64>,
+    // Indexed by RaisePhaseKind, defaults to a single public phase over the whole raise
+    pub phases: Option<[RaisePhase; 3]>,
+    // Defaults to the protocol's linear curve
+    pub curve: Option<CurveKind>,
+    // Defaults to the protocol's owner fee on trades and none on contributions
+    pub owner_fees: Option<OwnerFees>,
+}
+
 #[derive(Accounts)]
 #[instruction(pool_name: String)]
 pub struct CreatePool<'info> {
@@ -20,29 +41,52 @@ pub struct CreatePool<'info> {
     )]
     pub fee_vault: Box<Account<'info, FeeVault>>,
 
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, ProtocolConfig>>,
+
     #[account(
         init,
         seeds = [POOL_SEED, payer.key().as_ref(), pool_name.as_ref()],
         bump,
         payer = payer,
-        space = 64 + 24 + 10 + 8 +  std::mem::size_of::<Pool>(),
+        space = 8 + Pool::INIT_SPACE,
     )]
     pub pool: Box<Account<'info, Pool>>,
 
     #[account(
         init,
-        seeds = [POOL_WSOL_VAULT_SEED, pool.key().as_ref()],
+        seeds = [POOL_QUOTE_VAULT_SEED, pool.key().as_ref()],
         bump,
         payer = payer,
-        token::mint = wsol_mint,
+        token::mint = quote_mint,
         token::authority = pool,
     )]
-    pub pool_wsol_vault: Box<Account<'info, TokenAccount>>,
+    pub pool_quote_vault: Box<Account<'info, TokenAccount>>,
 
+    // The first pool raising in a mint opens its fee ledger
     #[account(
-        constraint = wsol_mint.key() == NATIVE_SOL_SPL_MINT,
+        init_if_needed,
+        seeds = [FEE_LEDGER_SEED, quote_mint.key().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + FeeLedger::INIT_SPACE,
     )]
-    pub wsol_mint: Box<Account<'info, Mint>>,
+    pub fee_ledger: Box<Account<'info, FeeLedger>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [FEE_LEDGER_VAULT_SEED, quote_mint.key().as_ref()],
+        bump,
+        payer = payer,
+        token::mint = quote_mint,
+        token::authority = fee_ledger,
+    )]
+    pub fee_ledger_vault: Box<Account<'info, TokenAccount>>,
+
+    pub quote_mint: Box<Account<'info, Mint>>,
 
     #[account(mut)]
     pub payer: Signer<'info>,
@@ -54,23 +98,79 @@ impl<'info> CreatePool<'info> {
     pub fn handler(
         &mut self,
         pool_name: String,
-        symbol: String,
-        raise_goal: u64,
+        params: CreatePoolParams,
         pool_bump: u8,
+        fee_ledger_bump: u8,
     ) -> Result<()> {
-        if pool_name.is_empty() || pool_name.len() > 24 {
+        if pool_name.is_empty() || pool_name.len() > 24 || params.symbol.len() > 10 {
             return Err(ErrorCode::InvalidPoolName.into());
         }
 
-        if raise_goal == 0 {
+        if params.raise_goal == 0 || params.min_raise > params.raise_goal {
             return Err(ErrorCode::InvalidAmount.into());
         }
 
+        let min_contribution = params.min_contribution.unwrap_or(0);
+        let max_contribution_per_wallet = params.max_contribution_per_wallet.unwrap_or(0);
+        if min_contribution > params.raise_goal
+            || params.max_contribution_per_wallet == Some(0)
+            || (max_contribution_per_wallet != 0 && max_contribution_per_wallet < min_contribution)
+        {
+            return Err(ErrorCode::InvalidContributionLimits.into());
+        }
+
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let raise_start = params.raise_start.max(current_time);
+        if raise_start - current_time > self.config.max_raise_start_delay {
+            return Err(ErrorCode::InvalidRaiseStart.into());
+        }
+
+        if params.raise_duration < self.config.min_raise_duration
+            || params.raise_duration > self.config.max_raise_duration
+        {
+            return Err(ErrorCode::InvalidRaiseDuration.into());
+        }
+
+        let raise_end = raise_start
+            .checked_add(params.raise_duration)
+            .ok_or(ErrorCode::InvalidCalculation)?;
+
+        let mut phases = params.phases.unwrap_or_else(|| {
+            let mut phases = [RaisePhase::default(); 3];
+            phases[RaisePhaseKind::Public as usize].start = raise_start;
+            phases
+        });
+        // Phases scheduled before a clamped raise start open with it
+        for phase in phases.iter_mut().filter(|phase| phase.start != 0) {
+            phase.start = phase.start.max(raise_start);
+        }
+        RaisePhase::validate_schedule(&phases, raise_start, raise_end)?;
+
+        let curve = params.curve.unwrap_or(CurveKind::Linear {
+            k: self.config.k,
+            base_price: self.config.base_droplet_price,
+        });
+        curve.validate()?;
+
+        let owner_fees = params
+            .owner_fees
+            .unwrap_or_else(|| OwnerFees::from_config(&self.config));
+        owner_fees.validate(&self.config)?;
+
+        let pool_term = params
+            .maturity_date
+            .checked_sub(raise_end)
+            .ok_or(ErrorCode::InvalidMaturityDate)?;
+        if pool_term < self.config.min_pool_term || pool_term > self.config.max_pool_term {
+            return Err(ErrorCode::InvalidMaturityDate.into());
+        }
+
         // Transfer the pool creation fee in SOL from the payer to the fee vault
         let fee_vault_ix = solana_program::system_instruction::transfer(
             &self.payer.key(),
             &self.fee_vault.key(),
-            POOL_CREATION_FEE,
+            self.config.pool_creation_fee,
         );
         solana_program::program::invoke(
             &fee_vault_ix,
@@ -79,19 +179,47 @@ impl<'info> CreatePool<'info> {
                 self.fee_vault.to_account_info(),
             ],
         )?;
+        self.fee_vault
+            .counters
+            .record_fee(self.config.pool_creation_fee)?;
+
+        if self.fee_ledger.mint == Pubkey::default() {
+            self.fee_ledger.mint = self.quote_mint.key();
+            self.fee_ledger.vault = self.fee_ledger_vault.key();
+            self.fee_ledger.bump = fee_ledger_bump;
+        }
 
         self.pool.owner = self.payer.key();
         self.pool.uri = "".to_string();
         self.pool.name = pool_name;
-        self.pool.symbol = symbol;
+        self.pool.symbol = params.symbol;
         self.pool.droplet_mint = Pubkey::default();
+        self.pool.quote_mint = self.quote_mint.key();
+        self.pool.quote_decimals = self.quote_mint.decimals;
         self.pool.droplet_supply = 0;
         self.pool.droplet_liquidity = 0;
-        self.pool.raise_goal = raise_goal;
+        self.pool.owner_liquidity = 0;
+        self.pool.curve = curve;
+        self.pool.owner_fees = owner_fees;
+        self.pool.raise_goal = params.raise_goal;
+        self.pool.min_raise = params.min_raise;
         self.pool.total_raised = 0;
-        self.pool.raise_period_end = Clock::get()?.unix_timestamp + 72 * 60 * 60; // 3 days
-        self.pool.maturity_date = Clock::get()?.unix_timestamp + 365 * 24 * 60 * 60; // 1 year
-        self.pool.is_initialized = false;
+        self.pool.min_contribution = min_contribution;
+        self.pool.max_contribution_per_wallet = max_contribution_per_wallet;
+        self.pool.allowlist_root = [0; 32];
+        self.pool.guaranteed_reserve = 0;
+        self.pool.guaranteed_raised = 0;
+        self.pool.phases = phases;
+        self.pool.raise_period_start = raise_start;
+        self.pool.raise_period_end = raise_end;
+        self.pool.maturity_date = params.maturity_date;
+        self.pool.settled_supply = 0;
+        self.pool.settled_vault_balance = 0;
+        self.pool.asset_count = 0;
+        self.pool.status = PoolStatus::Created;
+        self.pool.is_filled = false;
+        self.pool.halted = false;
+        self.pool.halted_by_admin = false;
         self.pool.bump = pool_bump;
         self.moonpool.pools += 1;
 
@@ -1,4 +1,5 @@
 use crate::errors::ErrorCode;
+use crate::events::PoolStatusChanged;
 use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
@@ -20,7 +21,6 @@ pub struct CreatePoolMint<'info> {
         seeds = [POOL_SEED, payer.key().as_ref(), pool.name.as_ref()],
         bump,
         constraint = pool.owner == payer.key(),
-        constraint = pool.is_initialized == false,
     )]
     pub pool: Box<Account<'info, Pool>>,
 
@@ -36,12 +36,12 @@ pub struct CreatePoolMint<'info> {
 
     #[account(
         mut,
-        seeds = [POOL_WSOL_VAULT_SEED, pool.key().as_ref()],
+        seeds = [POOL_QUOTE_VAULT_SEED, pool.key().as_ref()],
         bump,
-        token::mint = wsol_mint,
+        token::mint = quote_mint,
         token::authority = pool,
     )]
-    pub pool_wsol_vault: Box<Account<'info, TokenAccount>>,
+    pub pool_quote_vault: Box<Account<'info, TokenAccount>>,
 
     #[account(
         init,
@@ -70,9 +70,9 @@ pub struct CreatePoolMint<'info> {
     pub payer: Signer<'info>,
 
     #[account(
-        constraint = wsol_mint.key() == NATIVE_SOL_SPL_MINT,
+        constraint = quote_mint.key() == pool.quote_mint,
     )]
-    pub wsol_mint: Box<Account<'info, Mint>>,
+    pub quote_mint: Box<Account<'info, Mint>>,
 
     /// CHECK: Metaplex program
     pub token_metadata_program: UncheckedAccount<'info>,
@@ -84,6 +84,13 @@ pub struct CreatePoolMint<'info> {
 
 impl<'info> CreatePoolMint<'info> {
     pub fn handler(&mut self, metadata_uri: String) -> Result<()> {
+        self.pool.ensure_status(&[PoolStatus::Created])?;
+
+        // The pool account is sized for a 64 byte uri
+        if metadata_uri.len() > 64 {
+            return Err(ErrorCode::InvalidMetadataUri.into());
+        }
+
         if self.token_metadata_program.key() != mpl_token_metadata::ID {
             return Err(ErrorCode::InvalidAccount.into());
         }
@@ -132,39 +139,18 @@ impl<'info> CreatePoolMint<'info> {
             pool_signer,
         )?;
 
-        // // Each pool starts with 1 Billion droplets
-        // // Mint droplets to the pool
-
-        // let amount = 1_000_000_000u64
-        //     .checked_mul(DROPLET_MINT_DECIMALS)
-        //     .ok_or_else(|| ErrorCode::InvalidAmount)?;
-
-        // let cpi_context = CpiContext::new_with_signer(
-        //     self.token_program.to_account_info(),
-        //     anchor_spl::token::MintTo {
-        //         mint: self.droplet_mint.to_account_info(),
-        //         to: self.pool_droplet_vault.to_account_info(),
-        //         authority: self.pool.to_account_info(),
-        //     },
-        //     pool_signer,
-        // );
-        // mint_to(cpi_context, amount)?;
-
-        // self.pool.droplet_supply = self
-        //     .pool
-        //     .droplet_supply
-        //     .checked_add(amount)
-        //     .ok_or_else(|| ErrorCode::InvalidAmount)?;
-
-        // self.pool.droplet_liquidity = self
-        //     .pool
-        //     .droplet_liquidity
-        //     .checked_add(amount)
-        //     .ok_or_else(|| ErrorCode::InvalidAmount)?;
-
         self.pool.uri = metadata_uri;
         self.pool.droplet_mint = self.droplet_mint.key();
-        self.pool.is_initialized = true;
+
+        let previous_status = self.pool.transition(PoolStatus::Raising)?;
+
+        emit!(PoolStatusChanged {
+            pool: self.pool.key(),
+            previous_status,
+            status: self.pool.status,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
         Ok(())
     }
 }
@@ -0,0 +1,39 @@
+use crate::errors::ErrorCode;
+use crate::events::PoolStatusChanged;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct FinalizeRaise<'info> {
+    #[account(mut)]
+    pub pool: Box<Account<'info, Pool>>,
+}
+
+impl<'info> FinalizeRaise<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+
+        if current_time <= self.pool.raise_period_end {
+            return Err(ErrorCode::RaisePeriodNotEnded.into());
+        }
+
+        // A pool that never opened its raise fails so it can still be closed
+        let next_status = if self.pool.status == PoolStatus::Created {
+            PoolStatus::Failed
+        } else if self.pool.total_raised >= self.pool.min_raise {
+            PoolStatus::Active
+        } else {
+            PoolStatus::Failed
+        };
+        let previous_status = self.pool.transition(next_status)?;
+
+        emit!(PoolStatusChanged {
+            pool: self.pool.key(),
+            previous_status,
+            status: self.pool.status,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+}
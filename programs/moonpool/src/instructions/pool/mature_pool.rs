@@ -0,0 +1,31 @@
+use crate::errors::ErrorCode;
+use crate::events::PoolStatusChanged;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct MaturePool<'info> {
+    #[account(mut)]
+    pub pool: Box<Account<'info, Pool>>,
+}
+
+impl<'info> MaturePool<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+
+        if current_time < self.pool.maturity_date {
+            return Err(ErrorCode::LockPeriodNotOver.into());
+        }
+
+        let previous_status = self.pool.transition(PoolStatus::Matured)?;
+
+        emit!(PoolStatusChanged {
+            pool: self.pool.key(),
+            previous_status,
+            status: self.pool.status,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+}
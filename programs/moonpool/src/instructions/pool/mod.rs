@@ -1,7 +1,21 @@
 pub mod add_asset;
+pub mod add_liquidity;
 pub mod buy_droplets;
+pub mod claim_refund;
+pub mod close_asset;
+pub mod close_member;
+pub mod close_pool;
+pub mod close_pool_account;
 pub mod contribute;
 pub mod create_pool;
 pub mod create_pool_mint;
+pub mod finalize_raise;
+pub mod mature_pool;
+pub mod quote;
+pub mod redeem;
 pub mod rescind_contribution;
 pub mod sell_droplets;
+pub mod set_allowlist;
+pub mod set_pool_halted;
+pub mod settle_pool;
+pub mod withdraw_liquidity;
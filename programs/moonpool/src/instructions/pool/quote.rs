@@ -0,0 +1,60 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+// Read-only pricing against the current pool state. Clients simulate these and read
+// the Borsh-encoded `TradeQuote` from the transaction's return data. Amounts are droplet
+// base units for buys and sells and quote base units for contributions, and quotes
+// price the whole trade rather than a whole droplet like `Pool::get_current_price`.
+#[derive(Accounts)]
+pub struct QuotePool<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, ProtocolConfig>>,
+
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [POOL_QUOTE_VAULT_SEED, pool.key().as_ref()],
+        bump,
+    )]
+    pub pool_quote_vault: Box<Account<'info, TokenAccount>>,
+}
+
+impl<'info> QuotePool<'info> {
+    pub fn handler_buy(&self, amount: u64) -> Result<TradeQuote> {
+        if amount == 0 {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+
+        self.pool
+            .quote_buy(&self.config, amount, self.pool_quote_vault.amount)
+    }
+
+    pub fn handler_sell(&self, amount: u64) -> Result<TradeQuote> {
+        if amount == 0 {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+
+        self.pool
+            .quote_sell(&self.config, amount, self.pool_quote_vault.amount)
+    }
+
+    pub fn handler_contribute(&self, amount: u64) -> Result<TradeQuote> {
+        if amount == 0 {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let (_, phase) = self
+            .pool
+            .active_phase(current_time)
+            .ok_or(ErrorCode::PoolNotInRaisePeriod)?;
+
+        // Quoted without a guarantee, as for a contributor holding none
+        self.pool.quote_contribute(&self.config, amount, &phase, 0)
+    }
+}
@@ -0,0 +1,189 @@
+use crate::errors::ErrorCode;
+use crate::events::Redeemed;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{burn, transfer, Burn, Mint, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct Redeem<'info> {
+    #[account(
+        seeds = [MOONPOOL_SEED],
+        bump,
+    )]
+    pub moonpool: Box<Account<'info, Moonpool>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, ProtocolConfig>>,
+
+    #[account(mut)]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [POOL_QUOTE_VAULT_SEED, pool.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = pool,
+    )]
+    pub pool_quote_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [DROPLET_MINT_SEED, pool.key().as_ref()],
+        bump,
+        constraint = droplet_mint.key() == pool.droplet_mint,
+    )]
+    pub droplet_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = droplet_mint,
+        associated_token::authority = payer,
+    )]
+    pub payer_droplet_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = quote_mint,
+        associated_token::authority = payer,
+    )]
+    pub payer_quote_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        constraint = quote_mint.key() == pool.quote_mint,
+    )]
+    pub quote_mint: Box<Account<'info, Mint>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Redeem<'info> {
+    // remaining_accounts holds one (asset, asset_vault, holder token account) triple per pool asset
+    pub fn handler(
+        &mut self,
+        amount: u64,
+        remaining_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        self.pool.ensure_exits_open(&self.moonpool, &self.config)?;
+        self.pool.ensure_status(&[PoolStatus::Settled])?;
+
+        if amount == 0 {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+
+        if remaining_accounts.len() != self.pool.asset_count as usize * 3 {
+            return Err(ErrorCode::InvalidAssetAccounts.into());
+        }
+
+        // Shares are taken against the outstanding supply, so the last redeemer empties every vault
+        let vault_amount = self.pool.pro_rata_share(
+            self.pool.holder_balance(self.pool_quote_vault.amount),
+            amount,
+        )?;
+
+        // Burn the redeemed droplets
+        let burn_context = CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.droplet_mint.to_account_info(),
+                from: self.payer_droplet_token_account.to_account_info(),
+                authority: self.payer.to_account_info(),
+            },
+        );
+        burn(burn_context, amount)?;
+
+        // Pay out the holder's share of the pool vault
+        let pool_key = self.pool.key();
+        let pool_owner_key = self.pool.owner.key();
+        let pool_seeds = &[
+            POOL_SEED,
+            pool_owner_key.as_ref(),
+            self.pool.name.as_bytes(),
+            &[self.pool.bump],
+        ];
+        let pool_signer = &[&pool_seeds[..]];
+
+        if vault_amount > 0 {
+            let vault_context = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.pool_quote_vault.to_account_info(),
+                    to: self.payer_quote_token_account.to_account_info(),
+                    authority: self.pool.to_account_info(),
+                },
+                pool_signer,
+            );
+            transfer(vault_context, vault_amount)?;
+        }
+
+        // Pay out the holder's share of every asset vault
+        let mut redeemed_mints: Vec<Pubkey> = Vec::with_capacity(self.pool.asset_count as usize);
+        for accounts in remaining_accounts.chunks(3) {
+            let mut asset = Account::<Asset>::try_from(&accounts[0])?;
+            let asset_vault = Account::<TokenAccount>::try_from(&accounts[1])?;
+            let holder_token_account = Account::<TokenAccount>::try_from(&accounts[2])?;
+
+            if asset.pool != pool_key
+                || asset.vault != asset_vault.key()
+                || holder_token_account.mint != asset.mint
+                || holder_token_account.owner != self.payer.key()
+                || redeemed_mints.contains(&asset.mint)
+            {
+                return Err(ErrorCode::InvalidAssetAccounts.into());
+            }
+            redeemed_mints.push(asset.mint);
+
+            let asset_amount = self.pool.pro_rata_share(asset_vault.amount, amount)?;
+            if asset_amount == 0 {
+                continue;
+            }
+
+            let asset_seeds = &[
+                ASSET_SEED,
+                pool_key.as_ref(),
+                asset.mint.as_ref(),
+                &[asset.bump],
+            ];
+            let asset_signer = &[&asset_seeds[..]];
+
+            let asset_context = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: accounts[1].clone(),
+                    to: accounts[2].clone(),
+                    authority: accounts[0].clone(),
+                },
+                asset_signer,
+            );
+            transfer(asset_context, asset_amount)?;
+
+            asset.amount = asset.amount.saturating_sub(asset_amount);
+            asset.exit(&crate::ID)?;
+        }
+
+        self.pool.droplet_supply = self
+            .pool
+            .droplet_supply
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InvalidCalculation)?;
+
+        emit!(Redeemed {
+            pool: pool_key,
+            holder: self.payer.key(),
+            droplets_burned: amount,
+            vault_amount,
+            assets_redeemed: redeemed_mints.len() as u32,
+        });
+
+        Ok(())
+    }
+}
@@ -1,9 +1,10 @@
 use crate::errors::ErrorCode;
+use crate::events::ContributionRescinded;
+use crate::fees::bps_fee;
 use crate::state::*;
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{burn, Mint, Token, TokenAccount};
+use anchor_spl::token::{burn, transfer, Burn, Mint, Token, TokenAccount, Transfer};
 
 #[derive(Accounts)]
 pub struct RescindContribution<'info> {
@@ -16,44 +17,72 @@ pub struct RescindContribution<'info> {
 
     #[account(
         mut,
-        seeds = [FEE_VAULT_SEED],
-        bump,
+        seeds = [FEE_LEDGER_SEED, quote_mint.key().as_ref()],
+        bump = fee_ledger.bump,
+    )]
+    pub fee_ledger: Box<Account<'info, FeeLedger>>,
+
+    #[account(
+        mut,
+        address = fee_ledger.vault,
     )]
-    pub fee_vault: Box<Account<'info, FeeVault>>,
+    pub fee_ledger_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, ProtocolConfig>>,
 
     #[account(mut)]
     pub pool: Box<Account<'info, Pool>>,
 
     #[account(
         mut,
-        seeds = [POOL_WSOL_VAULT_SEED, pool.key().as_ref()],
+        seeds = [POOL_QUOTE_VAULT_SEED, pool.key().as_ref()],
         bump,
+        token::mint = quote_mint,
+        token::authority = pool,
     )]
-    pub pool_wsol_vault: Box<Account<'info, TokenAccount>>,
+    pub pool_quote_vault: Box<Account<'info, TokenAccount>>,
 
     #[account(
         mut,
-        seeds = [DROPLET_MINT_SEED, pool.key().as_ref(), pool.name.as_bytes()],
+        seeds = [DROPLET_MINT_SEED, pool.key().as_ref()],
         bump,
+        constraint = droplet_mint.key() == pool.droplet_mint,
     )]
     pub droplet_mint: Box<Account<'info, Mint>>,
 
     #[account(
         mut,
-        constraint = pool.owner == pool_owner.key(),
+        associated_token::mint = droplet_mint,
+        associated_token::authority = payer,
     )]
-    pub pool_owner: SystemAccount<'info>,
+    pub seller_droplet_token_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
-        mut,
-        associated_token::mint = droplet_mint,
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = quote_mint,
         associated_token::authority = payer,
     )]
-    pub seller_droplet_token_account: Box<Account<'info, TokenAccount>>,
+    pub payer_quote_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [MEMBER_SEED, pool.key().as_ref(), payer.key().as_ref()],
+        bump = member.bump,
+    )]
+    pub member: Box<Account<'info, Member>>,
 
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    #[account(
+        constraint = quote_mint.key() == pool.quote_mint,
+    )]
+    pub quote_mint: Box<Account<'info, Mint>>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -63,76 +92,101 @@ impl<'info> RescindContribution<'info> {
     pub fn handler(&mut self, amount: u64) -> Result<()> {
         let current_time = Clock::get()?.unix_timestamp;
 
-        if current_time > self.pool.maturity_date {
-            return Err(ErrorCode::PoolMatured.into());
+        self.pool.ensure_exits_open(&self.moonpool, &self.config)?;
+        self.pool.ensure_status(&[PoolStatus::Raising])?;
+
+        if current_time < self.pool.raise_period_start || current_time > self.pool.raise_period_end
+        {
+            return Err(ErrorCode::PoolNotInRaisePeriod.into());
         }
 
-        if amount == 0 {
+        if amount == 0 || amount > self.pool.total_raised {
             return Err(ErrorCode::InvalidAmount.into());
         }
 
-        let droplets_value = self.pool.get_sell_price(amount)?;
-        let pool_owner_fee = droplets_value * POOL_OWNER_FEE / 100;
-        let program_fee = droplets_value * PROGRAM_FEE / 100;
+        // Exits are priced at the member's average raise price, not on the bonding curve
+        let droplets_to_burn = self.member.droplets_for_rescind(amount)?;
+        self.member.record_rescind(amount)?;
+        let rescind_fee = bps_fee(amount, self.config.rescind_fee_bps)?;
 
-        // Transfer pool owner fee from payer to pool owner
-        let pool_owner_ix = solana_program::system_instruction::transfer(
-            &self.payer.key(),
-            &self.pool.owner,
-            pool_owner_fee,
-        );
-        solana_program::program::invoke(
-            &pool_owner_ix,
-            &[
-                self.payer.to_account_info(),
-                self.pool_owner.to_account_info(),
-            ],
-        )?;
-
-        // Transfer the program fee in SOL from the payer to the fee vault
-        let fee_vault_ix = solana_program::system_instruction::transfer(
-            &self.payer.key(),
-            &self.fee_vault.key(),
-            program_fee,
-        );
-        solana_program::program::invoke(
-            &fee_vault_ix,
-            &[
-                self.payer.to_account_info(),
-                self.fee_vault.to_account_info(),
-            ],
-        )?;
-
-        // Burn the droplets
-        let cpi_context = CpiContext::new(
+        // Burn the droplets minted for the rescinded amount
+        let burn_context = CpiContext::new(
             self.token_program.to_account_info(),
-            anchor_spl::token::Burn {
+            Burn {
                 mint: self.droplet_mint.to_account_info(),
                 from: self.seller_droplet_token_account.to_account_info(),
                 authority: self.payer.to_account_info(),
             },
         );
-        burn(cpi_context, amount)?;
-        self.pool.droplet_supply -= amount;
-
-        // Transfer droplets value from pool vault to payer
-        **self
-            .pool_wsol_vault
-            .to_account_info()
-            .try_borrow_mut_lamports()? = self
-            .pool_wsol_vault
-            .to_account_info()
-            .lamports()
-            .checked_sub(droplets_value)
+        burn(burn_context, droplets_to_burn)?;
+
+        // Return the contribution from the pool vault, less the rescind fee
+        let pool_owner_key = self.pool.owner.key();
+        let pool_seeds = &[
+            POOL_SEED,
+            pool_owner_key.as_ref(),
+            self.pool.name.as_bytes(),
+            &[self.pool.bump],
+        ];
+        let pool_signer = &[&pool_seeds[..]];
+
+        if rescind_fee > 0 {
+            let fee_context = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.pool_quote_vault.to_account_info(),
+                    to: self.fee_ledger_vault.to_account_info(),
+                    authority: self.pool.to_account_info(),
+                },
+                pool_signer,
+            );
+            transfer(fee_context, rescind_fee)?;
+            self.fee_ledger
+                .record_fee(FeeSource::Rescind, rescind_fee)?;
+        }
+
+        let refund_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.pool_quote_vault.to_account_info(),
+                to: self.payer_quote_token_account.to_account_info(),
+                authority: self.pool.to_account_info(),
+            },
+            pool_signer,
+        );
+        let refund = amount
+            .checked_sub(rescind_fee)
+            .ok_or(ErrorCode::InvalidCalculation)?;
+        transfer(refund_context, refund)?;
+
+        self.pool.droplet_supply = self
+            .pool
+            .droplet_supply
+            .checked_sub(droplets_to_burn)
             .ok_or(ErrorCode::InvalidCalculation)?;
 
-        **self.payer.to_account_info().try_borrow_mut_lamports()? = self
-            .payer
-            .to_account_info()
-            .lamports()
-            .checked_add(droplets_value)
+        self.pool.total_raised = self
+            .pool
+            .total_raised
+            .checked_sub(amount)
             .ok_or(ErrorCode::InvalidCalculation)?;
 
+        // Rescinded quote leaves the vault, so it no longer deepens the curve
+        self.pool.droplet_liquidity = self.pool.droplet_liquidity.saturating_sub(amount);
+
+        if self.pool.total_raised < self.pool.raise_goal {
+            self.pool.is_filled = false;
+        }
+
+        emit!(ContributionRescinded {
+            pool: self.pool.key(),
+            contributor: self.payer.key(),
+            amount,
+            rescind_fee,
+            droplets_burned: droplets_to_burn,
+            total_raised: self.pool.total_raised,
+        });
+
         Ok(())
     }
 }
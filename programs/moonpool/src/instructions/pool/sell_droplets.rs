@@ -1,9 +1,8 @@
 use crate::errors::ErrorCode;
 use crate::state::*;
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{burn, Mint, Token, TokenAccount};
+use anchor_spl::token::{burn, transfer, Mint, Token, TokenAccount};
 
 #[derive(Accounts)]
 pub struct SellDroplets<'info> {
@@ -16,25 +15,40 @@ pub struct SellDroplets<'info> {
 
     #[account(
         mut,
-        seeds = [FEE_VAULT_SEED],
-        bump,
+        seeds = [FEE_LEDGER_SEED, quote_mint.key().as_ref()],
+        bump = fee_ledger.bump,
+    )]
+    pub fee_ledger: Box<Account<'info, FeeLedger>>,
+
+    #[account(
+        mut,
+        address = fee_ledger.vault,
+    )]
+    pub fee_ledger_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
     )]
-    pub fee_vault: Box<Account<'info, FeeVault>>,
+    pub config: Box<Account<'info, ProtocolConfig>>,
 
     #[account(mut)]
     pub pool: Box<Account<'info, Pool>>,
 
     #[account(
         mut,
-        seeds = [POOL_WSOL_VAULT_SEED, pool.key().as_ref()],
+        seeds = [POOL_QUOTE_VAULT_SEED, pool.key().as_ref()],
         bump,
+        token::mint = quote_mint,
+        token::authority = pool,
     )]
-    pub pool_wsol_vault: Box<Account<'info, TokenAccount>>,
+    pub pool_quote_vault: Box<Account<'info, TokenAccount>>,
 
     #[account(
         mut,
-        seeds = [DROPLET_MINT_SEED, pool.key().as_ref(), pool.name.as_bytes()],
+        seeds = [DROPLET_MINT_SEED, pool.key().as_ref()],
         bump,
+        constraint = droplet_mint.key() == pool.droplet_mint,
     )]
     pub droplet_mint: Box<Account<'info, Mint>>,
 
@@ -44,6 +58,14 @@ pub struct SellDroplets<'info> {
     )]
     pub pool_owner: SystemAccount<'info>,
 
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = quote_mint,
+        associated_token::authority = pool_owner,
+    )]
+    pub pool_owner_quote_token_account: Box<Account<'info, TokenAccount>>,
+
     #[account(
         mut,
         associated_token::mint = droplet_mint,
@@ -51,58 +73,87 @@ pub struct SellDroplets<'info> {
     )]
     pub seller_droplet_token_account: Box<Account<'info, TokenAccount>>,
 
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = quote_mint,
+        associated_token::authority = payer,
+    )]
+    pub payer_quote_token_account: Box<Account<'info, TokenAccount>>,
+
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    #[account(
+        constraint = quote_mint.key() == pool.quote_mint,
+    )]
+    pub quote_mint: Box<Account<'info, Mint>>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> SellDroplets<'info> {
-    pub fn handler(&mut self, amount: u64) -> Result<()> {
-        let current_time = Clock::get()?.unix_timestamp;
-
-        if current_time > self.pool.maturity_date {
-            return Err(ErrorCode::PoolMatured.into());
-        }
+    /// `min_proceeds` is what the seller keeps after both fees, in quote base units.
+    pub fn handler(&mut self, amount: u64, min_proceeds: u64, deadline: Option<i64>) -> Result<()> {
+        self.ensure_can_sell(deadline)?;
 
         if amount == 0 {
             return Err(ErrorCode::InvalidAmount.into());
         }
 
-        let droplets_value = self.pool.get_sell_price(amount)?;
-        let pool_owner_fee = droplets_value * POOL_OWNER_FEE / 100;
-        let program_fee = droplets_value * PROGRAM_FEE / 100;
+        let quote = self
+            .pool
+            .quote_sell(&self.config, amount, self.pool_quote_vault.amount)?;
+        if quote.settlement < min_proceeds {
+            return Err(ErrorCode::SlippageExceeded.into());
+        }
 
-        // Transfer pool owner fee from payer to pool owner
-        let pool_owner_ix = solana_program::system_instruction::transfer(
-            &self.payer.key(),
-            &self.pool.owner,
-            pool_owner_fee,
-        );
-        solana_program::program::invoke(
-            &pool_owner_ix,
-            &[
-                self.payer.to_account_info(),
-                self.pool_owner.to_account_info(),
-            ],
-        )?;
+        self.execute(&quote)
+    }
 
-        // Transfer the program fee in SOL from the payer to the fee vault
-        let fee_vault_ix = solana_program::system_instruction::transfer(
-            &self.payer.key(),
-            &self.fee_vault.key(),
-            program_fee,
-        );
-        solana_program::program::invoke(
-            &fee_vault_ix,
-            &[
-                self.payer.to_account_info(),
-                self.fee_vault.to_account_info(),
-            ],
+    /// Sells the fewest droplets that leave the seller at least `quote_amount` after fees.
+    pub fn handler_exact_out(
+        &mut self,
+        quote_amount: u64,
+        max_droplets_in: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        self.ensure_can_sell(deadline)?;
+
+        if quote_amount == 0 {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+
+        let quote = self.pool.quote_sell_exact_out(
+            &self.config,
+            quote_amount,
+            self.pool_quote_vault.amount,
         )?;
+        if quote.droplets > max_droplets_in {
+            return Err(ErrorCode::SlippageExceeded.into());
+        }
+
+        self.execute(&quote)
+    }
+
+    fn ensure_can_sell(&self, deadline: Option<i64>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+
+        Pool::ensure_before_deadline(deadline, current_time)?;
+
+        self.pool.ensure_sells_open(&self.moonpool)?;
+
+        self.pool.ensure_status(&[PoolStatus::Active])?;
+
+        if current_time > self.pool.maturity_date {
+            return Err(ErrorCode::PoolMatured.into());
+        }
 
+        Ok(())
+    }
+
+    fn execute(&mut self, quote: &TradeQuote) -> Result<()> {
         // Burn the droplets
         let cpi_context = CpiContext::new(
             self.token_program.to_account_info(),
@@ -112,26 +163,53 @@ impl<'info> SellDroplets<'info> {
                 authority: self.payer.to_account_info(),
             },
         );
-        burn(cpi_context, amount)?;
-        self.pool.droplet_supply -= amount;
-
-        // Transfer droplets value from pool vault to payer
-        **self
-            .pool_wsol_vault
-            .to_account_info()
-            .try_borrow_mut_lamports()? = self
-            .pool_wsol_vault
-            .to_account_info()
-            .lamports()
-            .checked_sub(droplets_value)
-            .ok_or(ErrorCode::InvalidCalculation)?;
-
-        **self.payer.to_account_info().try_borrow_mut_lamports()? = self
-            .payer
-            .to_account_info()
-            .lamports()
-            .checked_add(droplets_value)
-            .ok_or(ErrorCode::InvalidCalculation)?;
+        burn(cpi_context, quote.droplets)?;
+        self.pool.droplet_supply -= quote.droplets;
+
+        // Proceeds and both fees all come out of the pool vault
+        let pool_owner_key = self.pool.owner.key();
+        let pool_seeds = &[
+            POOL_SEED,
+            pool_owner_key.as_ref(),
+            self.pool.name.as_bytes(),
+            &[self.pool.bump],
+        ];
+        let pool_signer = &[&pool_seeds[..]];
+
+        let proceeds_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: self.pool_quote_vault.to_account_info(),
+                to: self.payer_quote_token_account.to_account_info(),
+                authority: self.pool.to_account_info(),
+            },
+            pool_signer,
+        );
+        transfer(proceeds_context, quote.settlement)?;
+
+        let pool_owner_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: self.pool_quote_vault.to_account_info(),
+                to: self.pool_owner_quote_token_account.to_account_info(),
+                authority: self.pool.to_account_info(),
+            },
+            pool_signer,
+        );
+        transfer(pool_owner_context, quote.pool_owner_fee)?;
+
+        let fee_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: self.pool_quote_vault.to_account_info(),
+                to: self.fee_ledger_vault.to_account_info(),
+                authority: self.pool.to_account_info(),
+            },
+            pool_signer,
+        );
+        transfer(fee_context, quote.program_fee)?;
+        self.fee_ledger
+            .record_fee(FeeSource::Sell, quote.program_fee)?;
 
         Ok(())
     }
@@ -0,0 +1,44 @@
+use crate::errors::ErrorCode;
+use crate::events::AllowlistSet;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetAllowlist<'info> {
+    #[account(
+        mut,
+        constraint = pool.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    pub owner: Signer<'info>,
+}
+
+impl<'info> SetAllowlist<'info> {
+    /// A zeroed root removes the allowlist, otherwise the raise is allowlisted until
+    /// `public_raise_at`, or until it ends when that's 0. `guaranteed_reserve` is the
+    /// total of the guaranteed allocations in the tree, held back from everyone else.
+    pub fn handler(
+        &mut self,
+        allowlist_root: [u8; 32],
+        public_raise_at: i64,
+        guaranteed_reserve: u64,
+    ) -> Result<()> {
+        self.pool
+            .ensure_status(&[PoolStatus::Created, PoolStatus::Raising])?;
+
+        let public_raise_at =
+            self.pool
+                .set_allowlist(allowlist_root, public_raise_at, guaranteed_reserve)?;
+
+        emit!(AllowlistSet {
+            pool: self.pool.key(),
+            allowlist_root,
+            public_raise_at,
+            guaranteed_reserve,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
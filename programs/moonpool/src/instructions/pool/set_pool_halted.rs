@@ -0,0 +1,38 @@
+use crate::errors::ErrorCode;
+use crate::events::PoolHaltSet;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPoolHalted<'info> {
+    #[account(
+        seeds = [MOONPOOL_SEED],
+        bump,
+    )]
+    pub moonpool: Box<Account<'info, Moonpool>>,
+
+    #[account(
+        mut,
+        constraint = pool.owner == authority.key()
+            || moonpool.admin == authority.key() @ ErrorCode::Unauthorized,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    pub authority: Signer<'info>,
+}
+
+impl<'info> SetPoolHalted<'info> {
+    pub fn handler(&mut self, halted: bool) -> Result<()> {
+        let by_admin = self.moonpool.admin == self.authority.key();
+        self.pool.set_halted(halted, by_admin)?;
+
+        emit!(PoolHaltSet {
+            pool: self.pool.key(),
+            authority: self.authority.key(),
+            halted,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use crate::errors::ErrorCode;
+use crate::events::PoolSettled;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+#[derive(Accounts)]
+pub struct SettlePool<'info> {
+    #[account(mut)]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [POOL_QUOTE_VAULT_SEED, pool.key().as_ref()],
+        bump,
+    )]
+    pub pool_quote_vault: Box<Account<'info, TokenAccount>>,
+}
+
+impl<'info> SettlePool<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+
+        // An active pool past maturity is matured and settled in one step
+        if self.pool.status == PoolStatus::Active {
+            if current_time < self.pool.maturity_date {
+                return Err(ErrorCode::LockPeriodNotOver.into());
+            }
+            self.pool.transition(PoolStatus::Matured)?;
+        }
+
+        self.pool.transition(PoolStatus::Settled)?;
+        self.pool.settled_supply = self.pool.droplet_supply;
+        self.pool.settled_vault_balance = self.pool_quote_vault.amount;
+
+        emit!(PoolSettled {
+            pool: self.pool.key(),
+            settled_supply: self.pool.settled_supply,
+            settled_vault_balance: self.pool.settled_vault_balance,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+}
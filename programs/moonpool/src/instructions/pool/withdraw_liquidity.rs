@@ -0,0 +1,85 @@
+use crate::errors::ErrorCode;
+use crate::events::LiquidityWithdrawn;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct WithdrawLiquidity<'info> {
+    #[account(
+        mut,
+        constraint = pool.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [POOL_QUOTE_VAULT_SEED, pool.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = pool,
+    )]
+    pub pool_quote_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = quote_mint,
+        associated_token::authority = owner,
+    )]
+    pub owner_quote_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        constraint = quote_mint.key() == pool.quote_mint,
+    )]
+    pub quote_mint: Box<Account<'info, Mint>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> WithdrawLiquidity<'info> {
+    /// Returns the owner's `add_liquidity` deposit from a failed pool, or from a settled
+    /// one after every droplet has been redeemed.
+    pub fn handler(&mut self) -> Result<()> {
+        let amount = self
+            .pool
+            .reclaimable_liquidity(self.pool_quote_vault.amount)?;
+
+        let pool_owner_key = self.pool.owner.key();
+        let pool_seeds = &[
+            POOL_SEED,
+            pool_owner_key.as_ref(),
+            self.pool.name.as_bytes(),
+            &[self.pool.bump],
+        ];
+        let pool_signer = &[&pool_seeds[..]];
+
+        let transfer_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.pool_quote_vault.to_account_info(),
+                to: self.owner_quote_token_account.to_account_info(),
+                authority: self.pool.to_account_info(),
+            },
+            pool_signer,
+        );
+        transfer(transfer_context, amount)?;
+
+        // Whatever curve sells spent of the deposit is gone, so nothing stays owed
+        self.pool.owner_liquidity = 0;
+
+        emit!(LiquidityWithdrawn {
+            pool: self.pool.key(),
+            owner: self.owner.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
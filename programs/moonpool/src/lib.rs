@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
 use instructions::*;
+use state::TradeQuote;
 
 declare_id!("6ebivbQFHXnU7TqinCBugwnQWNduvQ3q34Xrug8kTkc2");
 
@@ -8,9 +9,37 @@ pub mod moonpool {
     use super::*;
 
     pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        ctx.accounts.handler(ctx.bumps.config)
+    }
+
+    pub fn update_config(ctx: Context<UpdateConfig>, params: UpdateConfigParams) -> Result<()> {
+        ctx.accounts.handler(params)
+    }
+
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        ctx.accounts.handler(amount)
+    }
+
+    pub fn withdraw_quote_fees(ctx: Context<WithdrawQuoteFees>, amount: u64) -> Result<()> {
+        ctx.accounts.handler(amount)
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.handler(new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn cancel_admin_proposal(ctx: Context<CancelAdminProposal>) -> Result<()> {
         ctx.accounts.handler()
     }
 
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        ctx.accounts.handler(paused)
+    }
+
     pub fn initialize_raydium_lp(
         ctx: Context<InitializeRaydiumLp>,
         init_amount_0: u64,
@@ -24,34 +53,150 @@ pub mod moonpool {
     pub fn create_pool(
         ctx: Context<CreatePool>,
         pool_name: String,
-        symbol: String,
-        raise_goal: u64,
+        params: CreatePoolParams,
     ) -> Result<()> {
         ctx.accounts
-            .handler(pool_name, symbol, raise_goal, ctx.bumps.pool)
+            .handler(pool_name, params, ctx.bumps.pool, ctx.bumps.fee_ledger)
     }
 
     pub fn create_pool_mint(ctx: Context<CreatePoolMint>, metadata_uri: String) -> Result<()> {
         ctx.accounts.handler(metadata_uri)
     }
 
-    pub fn contribute(ctx: Context<Contribute>, amount: u64) -> Result<()> {
+    pub fn contribute(
+        ctx: Context<Contribute>,
+        amount: u64,
+        allowlist_proof: Option<AllowlistProof>,
+    ) -> Result<()> {
+        ctx.accounts
+            .handler(amount, allowlist_proof, ctx.bumps.member)
+    }
+
+    pub fn rescind_contribution(ctx: Context<RescindContribution>, amount: u64) -> Result<()> {
         ctx.accounts.handler(amount)
     }
 
     pub fn add_asset(ctx: Context<AddAsset>, amount: u64) -> Result<()> {
-        ctx.accounts.handler(amount)
+        ctx.accounts.handler(amount, ctx.bumps.asset)
     }
 
-    pub fn buy_droplets(ctx: Context<BuyDroplets>, amount: u64) -> Result<()> {
+    pub fn add_liquidity(ctx: Context<AddLiquidity>, amount: u64) -> Result<()> {
         ctx.accounts.handler(amount)
     }
 
-    pub fn sell_droplets(ctx: Context<SellDroplets>, amount: u64) -> Result<()> {
-        ctx.accounts.handler(amount)
+    pub fn withdraw_liquidity(ctx: Context<WithdrawLiquidity>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn buy_droplets(
+        ctx: Context<BuyDroplets>,
+        amount: u64,
+        max_cost: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        ctx.accounts.handler(amount, max_cost, deadline)
+    }
+
+    pub fn buy_droplets_exact_in(
+        ctx: Context<BuyDroplets>,
+        quote_amount: u64,
+        min_droplets_out: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        ctx.accounts
+            .handler_exact_in(quote_amount, min_droplets_out, deadline)
+    }
+
+    pub fn sell_droplets(
+        ctx: Context<SellDroplets>,
+        amount: u64,
+        min_proceeds: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        ctx.accounts.handler(amount, min_proceeds, deadline)
+    }
+
+    pub fn sell_droplets_exact_out(
+        ctx: Context<SellDroplets>,
+        quote_amount: u64,
+        max_droplets_in: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        ctx.accounts
+            .handler_exact_out(quote_amount, max_droplets_in, deadline)
+    }
+
+    pub fn quote_buy(ctx: Context<QuotePool>, amount: u64) -> Result<TradeQuote> {
+        ctx.accounts.handler_buy(amount)
+    }
+
+    pub fn quote_sell(ctx: Context<QuotePool>, amount: u64) -> Result<TradeQuote> {
+        ctx.accounts.handler_sell(amount)
+    }
+
+    pub fn quote_contribute(ctx: Context<QuotePool>, amount: u64) -> Result<TradeQuote> {
+        ctx.accounts.handler_contribute(amount)
+    }
+
+    pub fn finalize_raise(ctx: Context<FinalizeRaise>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn mature_pool(ctx: Context<MaturePool>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn settle_pool(ctx: Context<SettlePool>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn redeem<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Redeem<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.handler(amount, ctx.remaining_accounts)
+    }
+
+    pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn close_asset(ctx: Context<CloseAsset>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn close_pool_account(ctx: Context<ClosePoolAccount>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn close_member(ctx: Context<CloseMember>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn set_allowlist(
+        ctx: Context<SetAllowlist>,
+        allowlist_root: [u8; 32],
+        public_raise_at: i64,
+        guaranteed_reserve: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .handler(allowlist_root, public_raise_at, guaranteed_reserve)
+    }
+
+    pub fn set_pool_halted(ctx: Context<SetPoolHalted>, halted: bool) -> Result<()> {
+        ctx.accounts.handler(halted)
     }
 }
 
+mod curve;
 mod errors;
+mod events;
+mod fees;
 mod instructions;
+mod math;
+mod merkle;
 mod state;
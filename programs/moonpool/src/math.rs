@@ -0,0 +1,330 @@
+use crate::errors::ErrorCode;
+use anchor_lang::prelude::*;
+
+/// Scale of fixed-point ratios, `K_PRECISION` represents 1.0.
+pub const K_PRECISION: u128 = 1_000_000_000;
+
+/// Most predicate evaluations `last_fitting` makes before giving up.
+pub const MAX_SEARCH_STEPS: u32 = 32;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+/// Computes `a * b / denominator` with a 256-bit intermediate product, so only a
+/// quotient that doesn't fit in a u128 fails.
+pub fn mul_div(a: u128, b: u128, denominator: u128, rounding: Rounding) -> Result<u128> {
+    if denominator == 0 {
+        return Err(ErrorCode::InvalidCalculation.into());
+    }
+
+    let (hi, lo) = full_mul(a, b);
+    if hi >= denominator {
+        return Err(ErrorCode::InvalidCalculation.into());
+    }
+
+    let (quotient, remainder) = if hi == 0 {
+        (lo / denominator, lo % denominator)
+    } else {
+        div_rem_wide(hi, lo, denominator)
+    };
+
+    if rounding == Rounding::Up && remainder != 0 {
+        return quotient
+            .checked_add(1)
+            .ok_or(ErrorCode::InvalidCalculation.into());
+    }
+
+    Ok(quotient)
+}
+
+/// Raises a fixed-point `base` at `precision` to an integer power by squaring, every
+/// step rounds the same way so the result bounds the exact power from that side.
+pub fn pow_fixed(base: u128, exponent: u64, precision: u128, rounding: Rounding) -> Result<u128> {
+    let mut result = precision;
+    let mut base = base;
+    let mut exponent = exponent;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_div(result, base, precision, rounding)?;
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = mul_div(base, base, precision, rounding)?;
+        }
+    }
+    Ok(result)
+}
+
+/// Largest integer whose square doesn't exceed `value`.
+pub fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+
+    // Newton's method only steps down from a start above the root
+    let bits = u128::BITS - value.leading_zeros();
+    let mut root = 1u128 << bits.div_ceil(2);
+    loop {
+        let next = (root + value / root) / 2;
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
+
+/// Largest value up to `max` that `fits`, for a predicate that holds up to some edge and
+/// fails past it, or `None` if even 0 doesn't fit. Strides away from `estimate`, doubling
+/// each time, then bisects the bracket, so an estimate `d` off the edge takes about
+/// `2 * log2(d)` evaluations. Fails rather than exceed `MAX_SEARCH_STEPS` of them.
+pub fn last_fitting(
+    estimate: u64,
+    max: u64,
+    mut fits: impl FnMut(u64) -> Result<bool>,
+) -> Result<Option<u64>> {
+    let mut steps = 0;
+    let mut check = |value: u64| {
+        steps += 1;
+        if steps > MAX_SEARCH_STEPS {
+            return Err(ErrorCode::InvalidCalculation.into());
+        }
+        fits(value)
+    };
+
+    // `low` fits and `high` doesn't
+    let estimate = estimate.min(max);
+    let mut stride = 1u64;
+    let (mut low, mut high) = if check(estimate)? {
+        let mut low = estimate;
+        loop {
+            if low == max {
+                return Ok(Some(max));
+            }
+            let probe = low.saturating_add(stride).min(max);
+            if !check(probe)? {
+                break (low, probe);
+            }
+            low = probe;
+            stride = stride.saturating_mul(2);
+        }
+    } else {
+        let mut high = estimate;
+        loop {
+            if high == 0 {
+                return Ok(None);
+            }
+            let probe = high.saturating_sub(stride);
+            if check(probe)? {
+                break (probe, high);
+            }
+            high = probe;
+            stride = stride.saturating_mul(2);
+        }
+    };
+
+    while high - low > 1 {
+        let middle = low + (high - low) / 2;
+        if check(middle)? {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    Ok(Some(low))
+}
+
+pub fn to_u64(value: u128) -> Result<u64> {
+    u64::try_from(value).map_err(|_| ErrorCode::InvalidCalculation.into())
+}
+
+// Splits both factors into 64-bit limbs and returns the (high, low) halves of the product
+fn full_mul(a: u128, b: u128) -> (u128, u128) {
+    let mask = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & mask);
+    let (b_hi, b_lo) = (b >> 64, b & mask);
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    let cross = (lo_lo >> 64) + (hi_lo & mask) + (lo_hi & mask);
+    let lo = (cross << 64) | (lo_lo & mask);
+    let hi = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (cross >> 64);
+    (hi, lo)
+}
+
+// Shift-subtract long division of a 256-bit value, `hi < denominator` keeps the quotient in range
+fn div_rem_wide(hi: u128, lo: u128, denominator: u128) -> (u128, u128) {
+    let mut remainder = hi;
+    let mut quotient = 0u128;
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((lo >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= denominator {
+            remainder = remainder.wrapping_sub(denominator);
+            quotient |= 1;
+        }
+    }
+    (quotient, remainder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_mul() {
+        assert_eq!(full_mul(0, u128::MAX), (0, 0));
+        assert_eq!(full_mul(1, u128::MAX), (0, u128::MAX));
+        assert_eq!(full_mul(u128::MAX, u128::MAX), (u128::MAX - 1, 1));
+        assert_eq!(full_mul(1 << 64, 1 << 64), (1, 0));
+        assert_eq!(full_mul(u128::MAX, 2), (1, u128::MAX - 1));
+
+        for a in (0..u64::MAX as u128).step_by(0x0123_4567_89ab_cdef) {
+            for b in (0..u64::MAX as u128).step_by(0x0fed_cba9_8765_4321) {
+                assert_eq!(full_mul(a, b), (0, a * b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_div_matches_reference() {
+        for a in 0..48u128 {
+            for b in 0..48u128 {
+                for denominator in 1..48u128 {
+                    let product = a * b;
+                    let down = product / denominator;
+                    let up = down + (product % denominator != 0) as u128;
+                    assert_eq!(mul_div(a, b, denominator, Rounding::Down).unwrap(), down);
+                    assert_eq!(mul_div(a, b, denominator, Rounding::Up).unwrap(), up);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_div_wide_intermediate() {
+        // The product overflows a u128 but the quotient doesn't
+        assert_eq!(
+            mul_div(u128::MAX, u128::MAX, u128::MAX, Rounding::Down).unwrap(),
+            u128::MAX
+        );
+        assert_eq!(
+            mul_div(u128::MAX, 1 << 64, 1 << 65, Rounding::Down).unwrap(),
+            u128::MAX >> 1
+        );
+        assert_eq!(
+            mul_div(u128::MAX, 1 << 64, 1 << 65, Rounding::Up).unwrap(),
+            (u128::MAX >> 1) + 1
+        );
+        assert_eq!(
+            mul_div(u128::MAX - 1, u128::MAX, u128::MAX, Rounding::Up).unwrap(),
+            u128::MAX - 1
+        );
+
+        // (2^100 + 3) * 2^100 / 2^90 = 2^110 + 3 * 2^10
+        let a = (1u128 << 100) + 3;
+        assert_eq!(
+            mul_div(a, 1 << 100, 1 << 90, Rounding::Down).unwrap(),
+            (1 << 110) + (3 << 10)
+        );
+
+        // 10^30 * 10^18 / 10^20 = 10^28
+        let e = |power: u32| 10u128.pow(power);
+        assert_eq!(mul_div(e(30), e(18), e(20), Rounding::Up).unwrap(), e(28));
+        assert_eq!(
+            mul_div(e(30) + 1, e(18), e(20), Rounding::Down).unwrap(),
+            e(28)
+        );
+        assert_eq!(
+            mul_div(e(30) + 1, e(18), e(20), Rounding::Up).unwrap(),
+            e(28) + 1
+        );
+    }
+
+    #[test]
+    fn test_pow_fixed() {
+        let p = K_PRECISION;
+        assert_eq!(pow_fixed(2 * p, 0, p, Rounding::Down).unwrap(), p);
+        assert_eq!(pow_fixed(2 * p, 10, p, Rounding::Down).unwrap(), 1024 * p);
+        assert_eq!(pow_fixed(p / 2, 3, p, Rounding::Down).unwrap(), p / 8);
+
+        // 1.01^100 = 2.704813829...
+        let down = pow_fixed(p + p / 100, 100, p, Rounding::Down).unwrap();
+        let up = pow_fixed(p + p / 100, 100, p, Rounding::Up).unwrap();
+        assert!(down <= 2_704_813_829 && up >= 2_704_813_830);
+        // Rounding drifts by at most an ulp per multiplication, scaled by the result
+        assert!(up - down <= 100);
+
+        for exponent in 0..40 {
+            assert_eq!(
+                pow_fixed(3 * p, exponent, p, Rounding::Up).unwrap(),
+                3u128.pow(exponent as u32) * p
+            );
+        }
+
+        assert!(pow_fixed(2 * p, 128, p, Rounding::Down).is_err());
+    }
+
+    #[test]
+    fn test_isqrt() {
+        for value in 0..10_000u128 {
+            let root = isqrt(value);
+            assert!(root * root <= value && (root + 1) * (root + 1) > value);
+        }
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+        assert_eq!(isqrt((u64::MAX as u128).pow(2)), u64::MAX as u128);
+        assert_eq!(isqrt((u64::MAX as u128).pow(2) - 1), u64::MAX as u128 - 1);
+        assert_eq!(isqrt(1 << 100), 1 << 50);
+    }
+
+    #[test]
+    fn test_last_fitting() {
+        let edges = [0u64, 1, 2, 1_000, 123_456_789, u64::MAX - 1, u64::MAX];
+        for edge in edges {
+            for estimate in [edge.saturating_sub(3), edge, edge.saturating_add(5)] {
+                let mut steps = 0;
+                let found = last_fitting(estimate, u64::MAX, |value| {
+                    steps += 1;
+                    Ok(value <= edge)
+                })
+                .unwrap();
+                assert_eq!(found, Some(edge));
+                assert!(steps <= 7, "{} steps from {} to {}", steps, estimate, edge);
+            }
+        }
+        // A far estimate still gets there, in logarithmically many steps
+        assert_eq!(
+            last_fitting(0, u64::MAX, |value| Ok(value <= 1_000)).unwrap(),
+            Some(1_000)
+        );
+
+        // Nothing fits, or everything up to `max` does
+        assert_eq!(last_fitting(10, 100, |_| Ok(false)).unwrap(), None);
+        assert_eq!(last_fitting(10, 100, |_| Ok(true)).unwrap(), Some(100));
+        assert_eq!(
+            last_fitting(500, 100, |value| Ok(value < 50)).unwrap(),
+            Some(49)
+        );
+
+        // Errors from the predicate surface, and so does a search that runs too long
+        assert!(last_fitting(1, 100, |_| Err(ErrorCode::InvalidAmount.into())).is_err());
+        assert!(last_fitting(0, u64::MAX, |value| Ok(value < 1 << 40)).is_err());
+    }
+
+    #[test]
+    fn test_mul_div_errors() {
+        assert!(mul_div(1, 1, 0, Rounding::Down).is_err());
+        assert!(mul_div(u128::MAX, 2, 1, Rounding::Down).is_err());
+        assert!(mul_div(u128::MAX, u128::MAX, u128::MAX - 1, Rounding::Down).is_err());
+        // An exact quotient at the top of the range doesn't round past it
+        assert!(mul_div(u128::MAX, 3, 3, Rounding::Up).is_ok());
+        assert!(to_u64(u64::MAX as u128).is_ok());
+        assert!(to_u64(u64::MAX as u128 + 1).is_err());
+    }
+}
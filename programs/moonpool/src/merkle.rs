@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+
+/// Leaf committed to by an allowlist root, an `allocation` of 0 leaves the address uncapped.
+pub fn allowlist_leaf(user: &Pubkey, allocation: u64) -> [u8; 32] {
+    keccak::hashv(&[user.as_ref(), &allocation.to_le_bytes()]).to_bytes()
+}
+
+/// Verifies a proof built with sorted pair hashing, so siblings need no position flags.
+pub fn verify(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            keccak::hashv(&[&node, sibling]).to_bytes()
+        } else {
+            keccak::hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    computed == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        if a <= b {
+            keccak::hashv(&[&a, &b]).to_bytes()
+        } else {
+            keccak::hashv(&[&b, &a]).to_bytes()
+        }
+    }
+
+    #[test]
+    fn test_verify_allowlist_proof() {
+        let users: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = users
+            .iter()
+            .enumerate()
+            .map(|(i, user)| allowlist_leaf(user, i as u64 * 1_000))
+            .collect();
+
+        // Three leaves, the last one is promoted to the second level unpaired
+        let left = hash_pair(leaves[0], leaves[1]);
+        let root = hash_pair(left, leaves[2]);
+
+        assert!(verify(&[leaves[1], leaves[2]], root, leaves[0]));
+        assert!(verify(&[leaves[0], leaves[2]], root, leaves[1]));
+        assert!(verify(&[left], root, leaves[2]));
+
+        // A wrong allocation or a proof for another leaf must not verify
+        assert!(!verify(
+            &[leaves[1], leaves[2]],
+            root,
+            allowlist_leaf(&users[0], 5_000)
+        ));
+        assert!(!verify(&[leaves[1], leaves[2]], root, leaves[2]));
+        assert!(!verify(&[], root, leaves[0]));
+    }
+}
@@ -1,39 +1,185 @@
+use crate::curve::{BondingCurve, CurveKind};
 use crate::errors;
+use crate::fees::{FeeBreakdown, FeeMode};
+use crate::math::{self, Rounding, K_PRECISION};
 use anchor_lang::prelude::*;
 use solana_program::{native_token::LAMPORTS_PER_SOL, pubkey};
 
 pub const MOONPOOL_SEED: &[u8] = b"moonpool";
 pub const POOL_SEED: &[u8] = b"pool";
-pub const POOL_WSOL_VAULT_SEED: &[u8] = b"wsol_vault";
+pub const POOL_QUOTE_VAULT_SEED: &[u8] = b"quote_vault";
 pub const POOL_DROPLET_VAULT_SEED: &[u8] = b"droplet_vault";
 pub const ASSET_SEED: &[u8] = b"asset";
 pub const ASSET_VAULT_SEED: &[u8] = b"asset_vault";
 pub const DROPLET_MINT_SEED: &[u8] = b"mint";
 pub const METADATA_SEED: &[u8] = b"metadata";
 pub const FEE_VAULT_SEED: &[u8] = b"fee_vault";
+pub const FEE_LEDGER_SEED: &[u8] = b"fee_ledger";
+pub const FEE_LEDGER_VAULT_SEED: &[u8] = b"fee_ledger_vault";
+pub const CONFIG_SEED: &[u8] = b"config";
+pub const MEMBER_SEED: &[u8] = b"member";
 
 pub const NATIVE_SOL_SPL_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
 
-pub const K: f64 = 0.001;
-pub const BASE_DROPLET_PRICE: u64 = 1000; // LAMPORTS
-pub const LIQUIDITY_FACTOR: f64 = 0.01;
+pub const K: u64 = 1_000_000; // 0.001 at K_PRECISION
+pub const BASE_DROPLET_PRICE: u64 = 1000; // Quote base units
+pub const LIQUIDITY_FACTOR: u64 = 10_000_000; // 0.01 per whole quote token, at K_PRECISION
 
 pub const DROPLET_MINT_DECIMALS: u64 = 10_u64.pow(6);
 pub const MAX_DROPLET_SUPPLY: u64 = 1_000_000_000_000_000;
 
 pub const POOL_CREATION_FEE: u64 = 50000000;
-pub const POOL_OWNER_FEE: u64 = 1; // 1%
-pub const PROGRAM_FEE: u64 = 1; // 1%
+pub const POOL_OWNER_FEE_BPS: u16 = 100; // 1%
+pub const MAX_POOL_OWNER_FEE_BPS: u16 = 500; // 5%
+pub const PROGRAM_FEE_BPS: u16 = 100; // 1%
+pub const RESCIND_FEE_BPS: u16 = 0; // 0%
+pub const MIN_RAISE_DURATION: i64 = 60 * 60; // 1 hour
+pub const MAX_RAISE_DURATION: i64 = 14 * 24 * 60 * 60; // 14 days
+pub const MIN_POOL_TERM: i64 = 24 * 60 * 60; // 1 day
+pub const MAX_POOL_TERM: i64 = 5 * 365 * 24 * 60 * 60; // 5 years
+pub const MAX_RAISE_START_DELAY: i64 = 30 * 24 * 60 * 60; // 30 days
+pub const ADMIN_TRANSFER_DELAY: i64 = 48 * 60 * 60; // 2 days
+
+// Bounds enforced on every ProtocolConfig update
+pub const MAX_POOL_CREATION_FEE: u64 = 10 * LAMPORTS_PER_SOL;
+pub const MAX_FEE_BPS: u16 = 1_000; // 10%
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const MAX_K: u64 = K_PRECISION as u64;
+pub const MAX_BASE_DROPLET_PRICE: u64 = LAMPORTS_PER_SOL;
+pub const RAISE_DURATION_LIMIT: i64 = 90 * 24 * 60 * 60; // 90 days
+pub const POOL_TERM_LIMIT: i64 = 10 * 365 * 24 * 60 * 60; // 10 years
+pub const RAISE_START_DELAY_LIMIT: i64 = 90 * 24 * 60 * 60; // 90 days
+pub const MIN_ADMIN_TRANSFER_DELAY: i64 = 60 * 60; // 1 hour
+pub const MAX_ADMIN_TRANSFER_DELAY: i64 = 30 * 24 * 60 * 60; // 30 days
 
 #[account]
 pub struct Moonpool {
     pub admin: Pubkey,
     pub pools: u64,
+    pub pending_admin: Pubkey,
+    pub pending_admin_eta: i64,
+    pub paused: bool,
+}
+
+#[account]
+#[derive(Default, InitSpace)]
+pub struct ProtocolConfig {
+    pub pool_creation_fee: u64,
+    pub pool_owner_fee_bps: u16, // Default for pools that don't pick their own
+    pub max_pool_owner_fee_bps: u16, // Upper bound on every owner fee a pool picks
+    pub program_fee_bps: u16,
+    pub fee_mode: FeeMode, // Whether buy and contribute fees sit on top of the amount
+    pub rescind_fee_bps: u16,
+    pub k: u64, // Fixed point at K_PRECISION
+    pub base_droplet_price: u64,
+    pub min_raise_duration: i64,
+    pub max_raise_duration: i64,
+    pub min_pool_term: i64,
+    pub max_pool_term: i64,
+    pub max_raise_start_delay: i64,
+    pub admin_transfer_delay: i64,
+    pub exits_open_when_paused: bool,
+    pub bump: u8,
 }
 
+/// Only pool creation fees are paid in lamports, so they're the only fees held here.
 #[account]
 pub struct FeeVault {
     pub admin: Pubkey,
+    pub counters: FeeCounters, // LAMPORTS
+}
+
+/// Quote token fees are kept apart per mint, in a token vault owned by the ledger.
+#[account]
+#[derive(InitSpace)]
+pub struct FeeLedger {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub counters: FeeCounters, // Quote base units
+    pub collected: TradeFees,  // Quote base units, split by source
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+pub struct FeeCounters {
+    pub total_collected: u64,
+    pub total_withdrawn: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+pub struct TradeFees {
+    pub contribute: u64,
+    pub buy: u64,
+    pub sell: u64,
+    pub rescind: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum FeeSource {
+    Contribute,
+    Buy,
+    Sell,
+    Rescind,
+}
+
+/// A priced droplet trade. `value` is what a buy or contribution credits to the pool
+/// vault, or the gross proceeds a sell takes out of it.
+/// `settlement` is what the trader pays on a buy or contribution, fees included, or
+/// receives on a sell, fees deducted.
+/// `droplets` and `resulting_supply` are droplet base units and every other field is
+/// quote base units, unlike `Pool::get_current_price`, which prices a whole droplet.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TradeQuote {
+    pub droplets: u64,
+    pub value: u64,
+    pub pool_owner_fee: u64,
+    pub program_fee: u64,
+    pub settlement: u64,
+    pub resulting_supply: u64,
+}
+
+impl TradeQuote {
+    // Buys and contributions charge the gross and credit the net, which inclusive fee
+    // rounding can leave slightly above the price
+    fn charged(droplets: u64, fees: &FeeBreakdown, resulting_supply: u64) -> Self {
+        TradeQuote {
+            droplets,
+            value: fees.net,
+            pool_owner_fee: fees.pool_owner_fee,
+            program_fee: fees.program_fee,
+            settlement: fees.gross,
+            resulting_supply,
+        }
+    }
+}
+
+/// Owner fees a pool charges on each flow, in basis points.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default, InitSpace,
+)]
+pub struct OwnerFees {
+    pub buy_bps: u16,
+    pub sell_bps: u16,
+    pub contribute_bps: u16,
+}
+
+impl OwnerFees {
+    // Contributions carried no owner fee before pools could pick their own
+    pub fn from_config(config: &ProtocolConfig) -> Self {
+        OwnerFees {
+            buy_bps: config.pool_owner_fee_bps,
+            sell_bps: config.pool_owner_fee_bps,
+            contribute_bps: 0,
+        }
+    }
+
+    pub fn validate(&self, config: &ProtocolConfig) -> Result<()> {
+        let max = config.max_pool_owner_fee_bps;
+        if self.buy_bps > max || self.sell_bps > max || self.contribute_bps > max {
+            return Err(errors::ErrorCode::OwnerFeeTooHigh.into());
+        }
+        Ok(())
+    }
 }
 
 #[account]
@@ -47,28 +193,145 @@ pub struct Pool {
     #[max_len(10)]
     pub symbol: String,
     pub droplet_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub quote_decimals: u8,
     pub droplet_supply: u64,
     pub droplet_liquidity: u64,
+    pub owner_liquidity: u64, // Quote base units from add_liquidity, never paid out to holders
+    pub curve: CurveKind,
+    pub owner_fees: OwnerFees,
     pub raise_goal: u64,
+    pub min_raise: u64,
     pub total_raised: u64,
+    pub min_contribution: u64,            // 0 when unset
+    pub max_contribution_per_wallet: u64, // 0 when unset
+    pub allowlist_root: [u8; 32],         // Zeroed when the raise is public
+    pub guaranteed_reserve: u64,          // Quote base units held back for guaranteed allocations
+    pub guaranteed_raised: u64,           // Quote base units claimed against guaranteed allocations
+    pub phases: [RaisePhase; 3],          // Indexed by RaisePhaseKind
+    pub raise_period_start: i64,
     pub raise_period_end: i64,
     pub maturity_date: i64,
-    pub is_initialized: bool,
+    pub settled_supply: u64,
+    pub settled_vault_balance: u64,
+    pub asset_count: u32,
+    pub status: PoolStatus,
+    pub is_filled: bool,
+    pub halted: bool,
+    pub halted_by_admin: bool, // Only the admin can lift its own halts
     pub bump: u8,
 }
 
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default, InitSpace,
+)]
+pub enum PoolStatus {
+    #[default]
+    Created,
+    Raising,
+    Active,
+    Matured,
+    Settled,
+    Failed,
+    Closed,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RaisePhaseKind {
+    Guaranteed,    // Allowlisted, each address claims the allocation reserved in its leaf
+    AllowlistFcfs, // Allowlisted, first come first served from what isn't reserved
+    Public,
+}
+
+impl RaisePhaseKind {
+    pub const ALL: [RaisePhaseKind; 3] = [
+        RaisePhaseKind::Guaranteed,
+        RaisePhaseKind::AllowlistFcfs,
+        RaisePhaseKind::Public,
+    ];
+
+    pub fn requires_allowlist(self) -> bool {
+        self != RaisePhaseKind::Public
+    }
+}
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default, InitSpace,
+)]
+pub struct RaisePhase {
+    pub start: i64,                    // 0 when the phase is skipped
+    pub wallet_cap: u64,               // Quote base units, 0 when unset
+    pub droplets_per_quote_token: u64, // 0 prices the phase off the raise goal
+}
+
+impl RaisePhase {
+    /// Phases run in order, the first enabled one opens the raise and every start falls
+    /// inside the raise period.
+    pub fn validate_schedule(
+        phases: &[RaisePhase; 3],
+        raise_start: i64,
+        raise_end: i64,
+    ) -> Result<()> {
+        let mut previous_start = None;
+        for phase in phases.iter().filter(|phase| phase.start != 0) {
+            let valid = match previous_start {
+                None => phase.start == raise_start,
+                Some(previous) => phase.start >= previous && phase.start <= raise_end,
+            };
+            if !valid {
+                return Err(errors::ErrorCode::InvalidRaisePhases.into());
+            }
+            previous_start = Some(phase.start);
+        }
+
+        if previous_start.is_none() {
+            return Err(errors::ErrorCode::InvalidRaisePhases.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl PoolStatus {
+    pub fn can_transition_to(self, next: PoolStatus) -> bool {
+        matches!(
+            (self, next),
+            (PoolStatus::Created, PoolStatus::Raising)
+                | (PoolStatus::Created, PoolStatus::Failed)
+                | (PoolStatus::Raising, PoolStatus::Active)
+                | (PoolStatus::Raising, PoolStatus::Failed)
+                | (PoolStatus::Active, PoolStatus::Matured)
+                | (PoolStatus::Matured, PoolStatus::Settled)
+                | (PoolStatus::Settled, PoolStatus::Closed)
+                | (PoolStatus::Failed, PoolStatus::Closed)
+        )
+    }
+}
+
 #[account]
 pub struct Asset {
     pub pool: Pubkey,
     pub mint: Pubkey,
     pub vault: Pubkey,
     pub amount: u64,
+    pub bump: u8,
 }
 
 #[account]
+#[derive(Default, InitSpace)]
 pub struct Member {
     pub pool: Pubkey,
     pub user: Pubkey,
+    pub contributed: u64,            // Quote base units
+    pub droplets_minted: u64,        // Droplets minted from contributions
+    pub rescinded: u64,              // Quote base units
+    pub refunded: u64,               // Quote base units
+    pub guaranteed_claimed: u64,     // Quote base units claimed against the guaranteed allocation
+    pub fcfs_claimed: u64,           // Quote base units taken first come first served
+    pub phase_contributed: [u64; 3], // Quote base units per RaisePhaseKind, before rescinds
+    pub first_contribution_at: i64,
+    pub last_contribution_at: i64,
+    pub bump: u8,
 }
 
 #[account]
@@ -79,7 +342,419 @@ pub struct Transaction {
     pub timestamp: i64,
 }
 
+impl ProtocolConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.pool_creation_fee > MAX_POOL_CREATION_FEE
+            || self.max_pool_owner_fee_bps > MAX_FEE_BPS
+            || self.pool_owner_fee_bps > self.max_pool_owner_fee_bps
+            || self.program_fee_bps > MAX_FEE_BPS
+            || self.rescind_fee_bps > MAX_FEE_BPS
+        {
+            return Err(errors::ErrorCode::InvalidConfig.into());
+        }
+
+        if self.k == 0 || self.k > MAX_K {
+            return Err(errors::ErrorCode::InvalidConfig.into());
+        }
+
+        if self.base_droplet_price == 0 || self.base_droplet_price > MAX_BASE_DROPLET_PRICE {
+            return Err(errors::ErrorCode::InvalidConfig.into());
+        }
+
+        if self.min_raise_duration <= 0
+            || self.min_raise_duration > self.max_raise_duration
+            || self.max_raise_duration > RAISE_DURATION_LIMIT
+        {
+            return Err(errors::ErrorCode::InvalidConfig.into());
+        }
+
+        if self.min_pool_term <= 0
+            || self.min_pool_term > self.max_pool_term
+            || self.max_pool_term > POOL_TERM_LIMIT
+        {
+            return Err(errors::ErrorCode::InvalidConfig.into());
+        }
+
+        if !(0..=RAISE_START_DELAY_LIMIT).contains(&self.max_raise_start_delay) {
+            return Err(errors::ErrorCode::InvalidConfig.into());
+        }
+
+        if !(MIN_ADMIN_TRANSFER_DELAY..=MAX_ADMIN_TRANSFER_DELAY)
+            .contains(&self.admin_transfer_delay)
+        {
+            return Err(errors::ErrorCode::InvalidConfig.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl FeeLedger {
+    pub fn record_fee(&mut self, source: FeeSource, amount: u64) -> Result<()> {
+        let counter = match source {
+            FeeSource::Contribute => &mut self.collected.contribute,
+            FeeSource::Buy => &mut self.collected.buy,
+            FeeSource::Sell => &mut self.collected.sell,
+            FeeSource::Rescind => &mut self.collected.rescind,
+        };
+        *counter = counter
+            .checked_add(amount)
+            .ok_or(errors::ErrorCode::InvalidCalculation)?;
+
+        self.counters.record_fee(amount)
+    }
+}
+
+impl FeeCounters {
+    pub fn record_fee(&mut self, amount: u64) -> Result<()> {
+        self.total_collected = self
+            .total_collected
+            .checked_add(amount)
+            .ok_or(errors::ErrorCode::InvalidCalculation)?;
+
+        Ok(())
+    }
+
+    pub fn record_withdrawal(&mut self, amount: u64) -> Result<()> {
+        self.total_withdrawn = self
+            .total_withdrawn
+            .checked_add(amount)
+            .ok_or(errors::ErrorCode::InvalidCalculation)?;
+
+        Ok(())
+    }
+}
+
+impl Member {
+    /// Contribution still held by the pool after rescinds.
+    pub fn net_contribution(&self) -> u64 {
+        self.contributed.saturating_sub(self.rescinded)
+    }
+
+    /// What's left of a guaranteed `allocation`, uncapped leaves carry no guarantee.
+    pub fn guarantee_left(&self, allocation: u64) -> u64 {
+        allocation.saturating_sub(self.guaranteed_claimed)
+    }
+
+    /// Splits a contribution proven against the allowlist between the member's guarantee
+    /// and first come first served capacity, returning the guaranteed part. Guaranteed
+    /// phases only take guarantees, which stay claimable for the rest of the raise. Pools
+    /// without that phase have no guarantees, and the leaf allocation caps FCFS claims
+    /// instead. Rescinds release neither, so they can't be reused.
+    pub fn claim_allocation(
+        &mut self,
+        kind: RaisePhaseKind,
+        amount: u64,
+        allocation: u64,
+        guarantees: bool,
+    ) -> Result<u64> {
+        let guaranteed = if guarantees {
+            amount.min(self.guarantee_left(allocation))
+        } else {
+            0
+        };
+        let unreserved = amount - guaranteed;
+
+        match kind {
+            RaisePhaseKind::Guaranteed if unreserved != 0 => {
+                return Err(errors::ErrorCode::AllowlistAllocationExceeded.into());
+            }
+            RaisePhaseKind::AllowlistFcfs => {
+                let claimed = self
+                    .fcfs_claimed
+                    .checked_add(unreserved)
+                    .ok_or(errors::ErrorCode::InvalidCalculation)?;
+                if !guarantees && allocation != 0 && claimed > allocation {
+                    return Err(errors::ErrorCode::AllowlistAllocationExceeded.into());
+                }
+                self.fcfs_claimed = claimed;
+            }
+            _ => {}
+        }
+
+        self.guaranteed_claimed += guaranteed;
+        Ok(guaranteed)
+    }
+
+    pub fn record_contribution(
+        &mut self,
+        kind: RaisePhaseKind,
+        amount: u64,
+        droplets: u64,
+        timestamp: i64,
+    ) -> Result<()> {
+        if self.first_contribution_at == 0 {
+            self.first_contribution_at = timestamp;
+        }
+        self.last_contribution_at = timestamp;
+        let phase_contributed = &mut self.phase_contributed[kind as usize];
+        *phase_contributed = phase_contributed
+            .checked_add(amount)
+            .ok_or(errors::ErrorCode::InvalidCalculation)?;
+        self.contributed = self
+            .contributed
+            .checked_add(amount)
+            .ok_or(errors::ErrorCode::InvalidCalculation)?;
+        self.droplets_minted = self
+            .droplets_minted
+            .checked_add(droplets)
+            .ok_or(errors::ErrorCode::InvalidCalculation)?;
+        Ok(())
+    }
+
+    /// Droplets to burn for rescinding `amount`, at the member's average raise price so
+    /// contributions from differently priced phases unwind exactly.
+    pub fn droplets_for_rescind(&self, amount: u64) -> Result<u64> {
+        if self.contributed == 0 || amount > self.net_contribution() {
+            return Err(errors::ErrorCode::RescindExceedsContribution.into());
+        }
+
+        let droplets_at = |rescinded: u64| {
+            (self.droplets_minted as u128 * rescinded as u128 / self.contributed as u128) as u64
+        };
+        Ok(droplets_at(self.rescinded + amount) - droplets_at(self.rescinded))
+    }
+
+    pub fn record_rescind(&mut self, amount: u64) -> Result<()> {
+        if amount > self.net_contribution() {
+            return Err(errors::ErrorCode::RescindExceedsContribution.into());
+        }
+        self.rescinded += amount;
+        Ok(())
+    }
+
+    pub fn record_refund(&mut self, amount: u64) -> Result<()> {
+        self.refunded = self
+            .refunded
+            .checked_add(amount)
+            .ok_or(errors::ErrorCode::InvalidCalculation)?;
+        Ok(())
+    }
+}
+
 impl Pool {
+    pub fn phase(&self, kind: RaisePhaseKind) -> &RaisePhase {
+        &self.phases[kind as usize]
+    }
+
+    pub fn phase_mut(&mut self, kind: RaisePhaseKind) -> &mut RaisePhase {
+        &mut self.phases[kind as usize]
+    }
+
+    /// The latest enabled phase that has started, later phases win on equal starts.
+    pub fn active_phase(&self, current_time: i64) -> Option<(RaisePhaseKind, RaisePhase)> {
+        RaisePhaseKind::ALL
+            .iter()
+            .rev()
+            .map(|kind| (*kind, *self.phase(*kind)))
+            .find(|(_, phase)| phase.start != 0 && phase.start <= current_time)
+    }
+
+    /// Points the raise at a new allowlist and returns when the public phase starts.
+    /// A zeroed root removes the allowlist and opens the whole raise to the public.
+    /// Otherwise an allowlisted phase opens the raise if none does yet, the guaranteed one
+    /// when `guaranteed_reserve` holds capacity back for it, and the public phase moves
+    /// to `public_raise_at`, or is skipped when it's 0.
+    pub fn set_allowlist(
+        &mut self,
+        allowlist_root: [u8; 32],
+        public_raise_at: i64,
+        guaranteed_reserve: u64,
+    ) -> Result<i64> {
+        let raise_start = self.raise_period_start;
+        let mut phases = self.phases;
+        let public_start = if allowlist_root == [0; 32] {
+            for kind in RaisePhaseKind::ALL
+                .iter()
+                .filter(|kind| kind.requires_allowlist())
+            {
+                phases[*kind as usize].start = 0;
+            }
+            raise_start
+        } else {
+            let allowlisted = RaisePhaseKind::ALL
+                .iter()
+                .any(|kind| kind.requires_allowlist() && phases[*kind as usize].start != 0);
+            if !allowlisted {
+                let kind = if guaranteed_reserve != 0 {
+                    RaisePhaseKind::Guaranteed
+                } else {
+                    RaisePhaseKind::AllowlistFcfs
+                };
+                phases[kind as usize].start = raise_start;
+            }
+            match public_raise_at {
+                0 => 0,
+                public_raise_at => public_raise_at.max(raise_start),
+            }
+        };
+        phases[RaisePhaseKind::Public as usize].start = public_start;
+        RaisePhase::validate_schedule(&phases, raise_start, self.raise_period_end)?;
+
+        // Only a guaranteed phase can claim a reserve, and it can't outgrow the raise
+        if guaranteed_reserve != 0
+            && (phases[RaisePhaseKind::Guaranteed as usize].start == 0
+                || guaranteed_reserve > self.raise_goal)
+        {
+            return Err(errors::ErrorCode::InvalidRaisePhases.into());
+        }
+
+        self.phases = phases;
+        self.allowlist_root = allowlist_root;
+        self.guaranteed_reserve = guaranteed_reserve;
+        Ok(public_start)
+    }
+
+    pub fn has_guarantees(&self) -> bool {
+        self.phase(RaisePhaseKind::Guaranteed).start != 0
+    }
+
+    // Reserved capacity nobody has claimed yet, which only guarantee holders can take
+    pub fn unclaimed_guarantees(&self) -> u64 {
+        self.guaranteed_reserve
+            .saturating_sub(self.guaranteed_raised)
+    }
+
+    /// The most a contribution can credit, where `guarantee` is what's left of the
+    /// contributor's guaranteed allocation.
+    pub fn contribution_capacity(&self, guarantee: u64) -> u64 {
+        let remaining_raise = self.raise_goal.saturating_sub(self.total_raised);
+        let reserved = self.unclaimed_guarantees();
+        remaining_raise
+            .saturating_sub(reserved)
+            .saturating_add(guarantee.min(reserved))
+            .min(remaining_raise)
+    }
+
+    pub fn record_guaranteed(&mut self, amount: u64) -> Result<()> {
+        self.guaranteed_raised = self
+            .guaranteed_raised
+            .checked_add(amount)
+            .ok_or(errors::ErrorCode::InvalidCalculation)?;
+        Ok(())
+    }
+
+    /// Checks a contribution against the pool's and the phase's per-wallet limits.
+    /// `contributed` is the member's net contribution before this one, and
+    /// `phase_contributed` what it has put into this phase.
+    pub fn ensure_contribution_limits(
+        &self,
+        phase: &RaisePhase,
+        requested: u64,
+        accepted: u64,
+        contributed: u64,
+        phase_contributed: u64,
+    ) -> Result<()> {
+        if requested < self.min_contribution {
+            return Err(errors::ErrorCode::ContributionBelowMinimum.into());
+        }
+
+        for (cap, before) in [
+            (self.max_contribution_per_wallet, contributed),
+            (phase.wallet_cap, phase_contributed),
+        ] {
+            let total = before
+                .checked_add(accepted)
+                .ok_or(errors::ErrorCode::InvalidCalculation)?;
+            if cap != 0 && total > cap {
+                return Err(errors::ErrorCode::WalletContributionCapExceeded.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    // Every instruction checks the pool status through this guard
+    pub fn ensure_status(&self, allowed: &[PoolStatus]) -> Result<()> {
+        if !allowed.contains(&self.status) {
+            return Err(errors::ErrorCode::InvalidPoolStatus.into());
+        }
+
+        Ok(())
+    }
+
+    // Moves the pool to `next`, returning the previous status
+    pub fn transition(&mut self, next: PoolStatus) -> Result<PoolStatus> {
+        if !self.status.can_transition_to(next) {
+            return Err(errors::ErrorCode::InvalidPoolStatus.into());
+        }
+
+        let previous = self.status;
+        self.status = next;
+        Ok(previous)
+    }
+
+    // A pool can be torn down once every droplet has been redeemed or refunded. Droplets
+    // sent to the pool's own droplet vault can't be redeemed by anyone, so they don't hold
+    // the pool open, and closing burns them.
+    pub fn ensure_closable(&self, stray_droplets: u64) -> Result<()> {
+        self.ensure_status(&[PoolStatus::Settled, PoolStatus::Failed])?;
+
+        if self.droplet_supply > stray_droplets {
+            return Err(errors::ErrorCode::PoolNotFullyRedeemed.into());
+        }
+
+        Ok(())
+    }
+
+    // Contributions, trading and deposits stop while the protocol or this pool is halted
+    pub fn ensure_trading_open(&self, moonpool: &Moonpool) -> Result<()> {
+        if moonpool.paused {
+            return Err(errors::ErrorCode::ProtocolPaused.into());
+        }
+
+        if self.halted {
+            return Err(errors::ErrorCode::PoolHalted.into());
+        }
+
+        Ok(())
+    }
+
+    // Owners can't lift an admin halt, and an admin halt stays one if the owner re-halts
+    pub fn set_halted(&mut self, halted: bool, by_admin: bool) -> Result<()> {
+        if !halted && self.halted_by_admin && !by_admin {
+            return Err(errors::ErrorCode::Unauthorized.into());
+        }
+
+        self.halted_by_admin = halted && (by_admin || self.halted_by_admin);
+        self.halted = halted;
+        Ok(())
+    }
+
+    // Sells price off the curve, so a pause or an admin halt stops them like any trade.
+    // Only an owner's halt leaves holders free to sell out.
+    pub fn ensure_sells_open(&self, moonpool: &Moonpool) -> Result<()> {
+        if self.halted && !self.halted_by_admin && !moonpool.paused {
+            return Ok(());
+        }
+
+        self.ensure_trading_open(moonpool)
+    }
+
+    // Redemption-type exits can be configured to stay open during a halt
+    pub fn ensure_exits_open(&self, moonpool: &Moonpool, config: &ProtocolConfig) -> Result<()> {
+        if config.exits_open_when_paused {
+            return Ok(());
+        }
+
+        self.ensure_trading_open(moonpool)
+    }
+
+    // Share of `balance` owed to `amount` droplets. Rounds down, so the holder of the
+    // last outstanding droplets receives whatever remains in the vault.
+    pub fn pro_rata_share(&self, balance: u64, amount: u64) -> Result<u64> {
+        if amount == 0 || amount > self.droplet_supply {
+            return Err(errors::ErrorCode::InvalidAmount.into());
+        }
+
+        let share = (balance as u128)
+            .checked_mul(amount as u128)
+            .ok_or(errors::ErrorCode::InvalidCalculation)?
+            / self.droplet_supply as u128;
+
+        u64::try_from(share).map_err(|_| errors::ErrorCode::InvalidCalculation.into())
+    }
+
     pub fn validate(&self, amount: u64) -> Result<()> {
         let new_supply = self
             .droplet_supply
@@ -93,80 +768,1697 @@ impl Pool {
         Ok(())
     }
 
-    fn get_liquidity_factor(liquidity: u64) -> f64 {
-        return 1.00 + liquidity as f64 * LIQUIDITY_FACTOR;
+    // 1 + droplet_liquidity * LIQUIDITY_FACTOR, with liquidity counted in whole quote
+    // tokens. Fixed point at K_PRECISION.
+    pub fn get_liquidity_factor(&self) -> Result<u128> {
+        let quote_unit = 10_u128
+            .checked_pow(self.quote_decimals as u32)
+            .ok_or(errors::ErrorCode::InvalidCalculation)?;
+        let depth = math::mul_div(
+            self.droplet_liquidity as u128,
+            LIQUIDITY_FACTOR as u128,
+            quote_unit,
+            Rounding::Down,
+        )?;
+        K_PRECISION
+            .checked_add(depth)
+            .ok_or(errors::ErrorCode::InvalidCalculation.into())
+    }
+
+    // Liquidity stretches the curve along the supply axis, so the price at `supply` is
+    // the base curve's price at supply / factor. Price impact shrinks by the factor,
+    // and trades are priced over the matching virtual interval scaled back up.
+    fn virtual_supply(supply: u64, factor: u128) -> Result<u64> {
+        math::mul_div(supply as u128, K_PRECISION, factor, Rounding::Down).and_then(math::to_u64)
+    }
+
+    pub fn ensure_before_deadline(deadline: Option<i64>, current_time: i64) -> Result<()> {
+        match deadline {
+            Some(deadline) if current_time > deadline => {
+                Err(errors::ErrorCode::TransactionExpired.into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn get_buy_price(&self, amount: u64) -> Result<u64> {
+        let factor = self.get_liquidity_factor()?;
+        let ending_supply = self
+            .droplet_supply
+            .checked_add(amount)
+            .ok_or(errors::ErrorCode::InvalidCalculation)?;
+        let start = Self::virtual_supply(self.droplet_supply, factor)?;
+        let end = Self::virtual_supply(ending_supply, factor)?;
+
+        let cost = self.curve.buy_cost(start, end - start)?;
+        math::mul_div(cost as u128, factor, K_PRECISION, Rounding::Up).and_then(math::to_u64)
     }
 
-    pub fn get_buy_price(&mut self, amount: u64) -> Result<u64> {
+    pub fn get_sell_price(&self, amount: u64) -> Result<u64> {
+        let factor = self.get_liquidity_factor()?;
+        let ending_supply = self
+            .droplet_supply
+            .checked_sub(amount)
+            .ok_or(errors::ErrorCode::InvalidAmount)?;
         if amount == 0 {
             return Err(errors::ErrorCode::InvalidAmount.into());
         }
+        let start = Self::virtual_supply(ending_supply, factor)?;
+        let end = Self::virtual_supply(self.droplet_supply, factor)?;
+        // Sells smaller than a virtual base unit are worth nothing on the curve
+        if start == end {
+            return Ok(0);
+        }
 
-        let ending_supply = self.droplet_supply + amount;
-        let price = K * ((ending_supply.pow(2) - self.droplet_supply.pow(2)) as f64) / 2.0;
-        Ok((price * BASE_DROPLET_PRICE as f64) as u64)
+        let proceeds = self.curve.sell_proceeds(end, end - start)?;
+        math::mul_div(proceeds as u128, factor, K_PRECISION, Rounding::Down).and_then(math::to_u64)
     }
 
-    pub fn get_sell_price(&mut self, amount: u64) -> Result<u64> {
-        if amount == 0 || amount > self.droplet_supply {
-            return Err(errors::ErrorCode::InvalidAmount.into());
+    // Buys pay at least the holders' pro-rata value per droplet, so buying on the curve
+    // and selling straight back at the floor can't come out ahead
+    pub fn get_buy_cost(&self, amount: u64, vault_balance: u64) -> Result<u64> {
+        let curve_cost = self.get_buy_price(amount)?;
+        if self.droplet_supply == 0 {
+            return Ok(curve_cost);
         }
 
-        let ending_supply = self.droplet_supply - amount;
-        let price = K * (self.droplet_supply.pow(2) - ending_supply.pow(2)) as f64 / 2.0;
-        Ok((price * BASE_DROPLET_PRICE as f64) as u64)
+        let floor = math::mul_div(
+            self.holder_balance(vault_balance) as u128,
+            amount as u128,
+            self.droplet_supply as u128,
+            Rounding::Up,
+        )
+        .and_then(math::to_u64)?;
+        Ok(curve_cost.max(floor))
     }
 
-    pub fn get_current_price(&mut self) -> Result<u64> {
-        Ok((K * self.droplet_supply as f64 * BASE_DROPLET_PRICE as f64) as u64)
+    // Sells pay at least the pro-rata share of the holders' balance, and never more than
+    // it, so the owner's deposit is never spent on them. A curve value too large to
+    // compute is more than any vault holds.
+    pub fn get_sell_value(&self, amount: u64, vault_balance: u64) -> Result<u64> {
+        let holder_balance = self.holder_balance(vault_balance);
+        let floor = self.pro_rata_share(holder_balance, amount)?;
+        let curve_value = self
+            .get_sell_price(amount)
+            .map_err(|_| errors::ErrorCode::PoolInsolvent)?;
+        let value = curve_value.max(floor);
+        if value > holder_balance {
+            return Err(errors::ErrorCode::PoolInsolvent.into());
+        }
+        Ok(value)
     }
 
-    // The price of each token in the funding round is c/r/LAMPORTS_PER_SOL.
-    // c is the max amount of droplets per pool - 1,000,000,000
-    // r is the amount of SOL to be raised
-    pub fn calculate_sol_to_droplets(&mut self, sol_amount: u64) -> Result<u64> {
-        let raise_goal = self.raise_goal as f64 / LAMPORTS_PER_SOL as f64;
+    // Marginal price of one whole droplet, DROPLET_MINT_DECIMALS base units, at the
+    // current supply. In quote base units, rounded down.
+    pub fn get_current_price(&self) -> Result<u64> {
+        let factor = self.get_liquidity_factor()?;
+        self.curve
+            .spot_price(Self::virtual_supply(self.droplet_supply, factor)?)
+    }
 
-        let droplets_per_sol = 1_000_000_000 as f64 / raise_goal;
-        let float_sol_amount = sol_amount as f64 / LAMPORTS_PER_SOL as f64;
-        let droplets_to_mint = float_sol_amount * droplets_per_sol;
+    pub fn record_liquidity(&mut self, amount: u64) -> Result<()> {
+        self.droplet_liquidity = self
+            .droplet_liquidity
+            .checked_add(amount)
+            .ok_or(errors::ErrorCode::InvalidCalculation)?;
+        Ok(())
+    }
 
-        let final_droplets_to_mint = droplets_to_mint * DROPLET_MINT_DECIMALS as f64;
+    // Owner deposits deepen the curve like any liquidity but stay the owner's to reclaim
+    pub fn record_owner_liquidity(&mut self, amount: u64) -> Result<()> {
+        self.record_liquidity(amount)?;
+        self.owner_liquidity = self
+            .owner_liquidity
+            .checked_add(amount)
+            .ok_or(errors::ErrorCode::InvalidCalculation)?;
+        Ok(())
+    }
 
-        Ok(final_droplets_to_mint as u64)
+    // The part of the quote vault that backs droplets, excluding the owner's deposit
+    pub fn holder_balance(&self, vault_balance: u64) -> u64 {
+        vault_balance.saturating_sub(self.owner_liquidity)
     }
-}
 
-// run a test to see if the price is correct
-#[cfg(test)]
-mod tests {
-    use super::*;
+    // Owners reclaim their deposit once a raise fails, or once every holder has redeemed.
+    // Holders are never paid out of it, so it's only short if the vault is.
+    pub fn reclaimable_liquidity(&self, vault_balance: u64) -> Result<u64> {
+        self.ensure_status(&[PoolStatus::Failed, PoolStatus::Settled])?;
+        if self.status == PoolStatus::Settled && self.droplet_supply != 0 {
+            return Err(errors::ErrorCode::InvalidPoolStatus.into());
+        }
 
-    #[test]
-    fn test_pool_pricing() {
-        let mut pool = Pool {
-            owner: Pubkey::default(),
-            uri: String::from(""),
-            name: String::from("Test Pool"),
-            symbol: String::from("TEST"),
-            droplet_mint: Pubkey::default(),
-            droplet_supply: 0,
-            droplet_liquidity: 0,
-            raise_goal: 300_000_000_000,
-            total_raised: 0,
-            raise_period_end: 0,
-            maturity_date: 0,
-            is_initialized: false,
-            bump: 0,
+        let amount = self.owner_liquidity.min(vault_balance);
+        if amount == 0 {
+            return Err(errors::ErrorCode::NoOwnerLiquidity.into());
+        }
+        Ok(amount)
+    }
+
+    pub fn quote_buy(
+        &self,
+        config: &ProtocolConfig,
+        amount: u64,
+        vault_balance: u64,
+    ) -> Result<TradeQuote> {
+        let cost = self.get_buy_cost(amount, vault_balance)?;
+        let fees = FeeBreakdown::for_net(
+            config.fee_mode,
+            cost,
+            self.owner_fees.buy_bps,
+            config.program_fee_bps,
+        )?;
+        let resulting_supply = self
+            .droplet_supply
+            .checked_add(amount)
+            .ok_or(errors::ErrorCode::InvalidCalculation)?;
+        Ok(TradeQuote::charged(amount, &fees, resulting_supply))
+    }
+
+    pub fn quote_sell(
+        &self,
+        config: &ProtocolConfig,
+        amount: u64,
+        vault_balance: u64,
+    ) -> Result<TradeQuote> {
+        // Sell fees always come out of the proceeds, the seller never pays in
+        let proceeds = self.get_sell_value(amount, vault_balance)?;
+        let fees =
+            FeeBreakdown::inclusive(proceeds, self.owner_fees.sell_bps, config.program_fee_bps)?;
+        let resulting_supply = self
+            .droplet_supply
+            .checked_sub(amount)
+            .ok_or(errors::ErrorCode::InvalidAmount)?;
+        Ok(TradeQuote {
+            droplets: amount,
+            value: fees.gross,
+            pool_owner_fee: fees.pool_owner_fee,
+            program_fee: fees.program_fee,
+            settlement: fees.net,
+            resulting_supply,
+        })
+    }
+
+    // Prices a contribution in `phase`, clamped at the raise goal and at the reserve
+    // held for other addresses' guarantees. `amount` is credited in full under exclusive
+    // fees and is the whole charge under inclusive ones. `guarantee` is what's left of
+    // the contributor's guaranteed allocation. Allowlist and per-wallet checks depend on
+    // the contributor and are left to `contribute`.
+    pub fn quote_contribute(
+        &self,
+        config: &ProtocolConfig,
+        amount: u64,
+        phase: &RaisePhase,
+        guarantee: u64,
+    ) -> Result<TradeQuote> {
+        let owner_bps = self.owner_fees.contribute_bps;
+        let program_bps = config.program_fee_bps;
+
+        // The excess over what's left to the contributor is never taken from the payer
+        if self.total_raised >= self.raise_goal {
+            return Err(errors::ErrorCode::PoolFilled.into());
+        }
+        let remaining_raise = self.contribution_capacity(guarantee);
+        if remaining_raise == 0 {
+            return Err(errors::ErrorCode::RaiseReservedForGuarantees.into());
+        }
+        let fees = match config.fee_mode {
+            FeeMode::Exclusive => {
+                FeeBreakdown::exclusive(amount.min(remaining_raise), owner_bps, program_bps)?
+            }
+            FeeMode::Inclusive => {
+                let fees = FeeBreakdown::inclusive(amount, owner_bps, program_bps)?;
+                if fees.net > remaining_raise {
+                    FeeBreakdown::inclusive_for_net(remaining_raise, owner_bps, program_bps)?
+                } else {
+                    fees
+                }
+            }
         };
+        if fees.net == 0 {
+            return Err(errors::ErrorCode::AmountNotEnough.into());
+        }
+
+        let droplets = self.calculate_quote_to_droplets(fees.net, phase)?;
+        self.validate(droplets)?;
+
+        Ok(TradeQuote::charged(
+            droplets,
+            &fees,
+            self.droplet_supply + droplets,
+        ))
+    }
 
-        // calculate the price of 1 SOL in droplets
-        let sol_amount = 0.23 * LAMPORTS_PER_SOL as f64;
-        let amount_of_droplets = pool.calculate_sol_to_droplets(sol_amount as u64).unwrap();
-        println!(
-            "{} SOL returns {} droplets",
-            sol_amount / LAMPORTS_PER_SOL as f64,
-            amount_of_droplets
+    // The largest buy whose cost plus fees fits `budget`
+    pub fn quote_buy_exact_in(
+        &self,
+        config: &ProtocolConfig,
+        budget: u64,
+        vault_balance: u64,
+    ) -> Result<TradeQuote> {
+        self.settle_buy_exact_in(config, budget, vault_balance)
+            .map(|(quote, _)| quote)
+    }
+
+    // Inverts the fees, the NAV floor and the curve for the amount the budget buys, then
+    // confirms it against real quotes. Buys the curve can't price, under a virtual base
+    // unit or too large to compute, are unaffordable. Also returns how many were priced.
+    fn settle_buy_exact_in(
+        &self,
+        config: &ProtocolConfig,
+        budget: u64,
+        vault_balance: u64,
+    ) -> Result<(TradeQuote, u32)> {
+        let max_amount = MAX_DROPLET_SUPPLY.saturating_sub(self.droplet_supply);
+        let estimate = self.buy_amount_for(config, budget, vault_balance)?;
+        let mut best = None;
+        let mut priced = 0;
+        let amount = math::last_fitting(estimate, max_amount, |amount| {
+            if amount == 0 {
+                return Ok(true);
+            }
+            priced += 1;
+            match self.quote_buy(config, amount, vault_balance) {
+                Ok(quote) if quote.settlement <= budget => {
+                    best = Some(quote);
+                    Ok(true)
+                }
+                Ok(_) => Ok(false),
+                Err(error)
+                    if error == errors::ErrorCode::InvalidAmount.into()
+                        || error == errors::ErrorCode::InvalidCalculation.into() =>
+                {
+                    Ok(false)
+                }
+                Err(error) => Err(error),
+            }
+        })?;
+
+        match (amount, best) {
+            (Some(amount), Some(quote)) if amount != 0 && quote.droplets == amount => {
+                Ok((quote, priced))
+            }
+            _ => Err(errors::ErrorCode::AmountNotEnough.into()),
+        }
+    }
+
+    // Droplets `budget` buys, from the largest cost it covers after fees. That cost bounds
+    // the buy on the curve, through the liquidity factor, and under the NAV floor.
+    fn buy_amount_for(
+        &self,
+        config: &ProtocolConfig,
+        budget: u64,
+        vault_balance: u64,
+    ) -> Result<u64> {
+        let owner_bps = self.owner_fees.buy_bps;
+        let program_bps = config.program_fee_bps;
+        let cost = match config.fee_mode {
+            FeeMode::Exclusive => math::mul_div(
+                budget as u128,
+                BPS_DENOMINATOR as u128,
+                BPS_DENOMINATOR as u128 + owner_bps as u128 + program_bps as u128,
+                Rounding::Down,
+            )
+            .and_then(math::to_u64)?,
+            FeeMode::Inclusive => FeeBreakdown::inclusive(budget, owner_bps, program_bps)?.net,
+        };
+
+        // The last real supply that maps onto the virtual supply the cost reaches
+        let factor = self.get_liquidity_factor()?;
+        let start = Self::virtual_supply(self.droplet_supply, factor)?;
+        let virtual_cost = math::mul_div(cost as u128, K_PRECISION, factor, Rounding::Down)
+            .and_then(math::to_u64)?;
+        let end = start.saturating_add(self.curve.buy_amount(start, virtual_cost)?);
+        let ending_supply = math::mul_div(end as u128 + 1, factor, K_PRECISION, Rounding::Up)? - 1;
+        let mut amount = ending_supply
+            .min(u64::MAX as u128)
+            .saturating_sub(self.droplet_supply as u128) as u64;
+
+        let holder_balance = self.holder_balance(vault_balance);
+        if self.droplet_supply != 0 && holder_balance != 0 {
+            let floor_amount = math::mul_div(
+                cost as u128,
+                self.droplet_supply as u128,
+                holder_balance as u128,
+                Rounding::Down,
+            );
+            amount = amount.min(floor_amount.map_or(u64::MAX, |floor_amount| {
+                floor_amount.min(u64::MAX as u128) as u64
+            }));
+        }
+        Ok(amount)
+    }
+
+    // The smallest sell whose proceeds after fees reach `target`. Sells the vault can't
+    // cover only get larger with the amount, so they count as reaching it and surface as
+    // insolvent if nothing smaller does.
+    pub fn quote_sell_exact_out(
+        &self,
+        config: &ProtocolConfig,
+        target: u64,
+        vault_balance: u64,
+    ) -> Result<TradeQuote> {
+        self.settle_sell_exact_out(config, target, vault_balance)
+            .map(|(quote, _)| quote)
+    }
+
+    // Estimates the sell like buys, then confirms the largest amount that falls short of
+    // `target` against real quotes. Also returns how many were priced.
+    fn settle_sell_exact_out(
+        &self,
+        config: &ProtocolConfig,
+        target: u64,
+        vault_balance: u64,
+    ) -> Result<(TradeQuote, u32)> {
+        if self.droplet_supply == 0 {
+            return Err(errors::ErrorCode::AmountNotEnough.into());
+        }
+
+        let estimate = self.sell_amount_for(config, target, vault_balance)?;
+        let mut reached = None;
+        let mut priced = 0;
+        let short = math::last_fitting(estimate - 1, self.droplet_supply, |amount| {
+            if amount == 0 {
+                return Ok(true);
+            }
+            priced += 1;
+            match self.quote_sell(config, amount, vault_balance) {
+                Ok(quote) if quote.settlement < target => Ok(true),
+                Ok(quote) => {
+                    reached = Some(quote);
+                    Ok(false)
+                }
+                Err(error) if error == errors::ErrorCode::PoolInsolvent.into() => Ok(false),
+                Err(error) => Err(error),
+            }
+        })?
+        .unwrap_or(0);
+
+        if short == self.droplet_supply {
+            return Err(errors::ErrorCode::AmountNotEnough.into());
+        }
+        let quote = match reached {
+            Some(quote) if quote.droplets == short + 1 => quote,
+            _ => self.quote_sell(config, short + 1, vault_balance)?,
+        };
+        Ok((quote, priced))
+    }
+
+    // Droplets that sell for the gross `target` takes before fees, through the liquidity
+    // factor on the curve or at the NAV floor, whichever takes fewer. Sells worth more
+    // than the holders' balance reach any target, so that caps the gross. At least 1, and
+    // at most the supply when neither reaches it.
+    fn sell_amount_for(
+        &self,
+        config: &ProtocolConfig,
+        target: u64,
+        vault_balance: u64,
+    ) -> Result<u64> {
+        let holder_balance = self.holder_balance(vault_balance);
+        let gross = FeeBreakdown::inclusive_for_net(
+            target,
+            self.owner_fees.sell_bps,
+            config.program_fee_bps,
+        )?
+        .gross
+        .min(holder_balance.saturating_add(1));
+
+        // The first real supply past the virtual supply the curve sells down to
+        let factor = self.get_liquidity_factor()?;
+        let end = Self::virtual_supply(self.droplet_supply, factor)?;
+        let virtual_gross = math::mul_div(gross as u128, K_PRECISION, factor, Rounding::Up)
+            .and_then(math::to_u64)?;
+        let curve_amount = match self.curve.sell_amount(end, virtual_gross) {
+            Ok(virtual_amount) => {
+                let start = (end - virtual_amount) as u128;
+                let ending_supply =
+                    math::mul_div(start + 1, factor, K_PRECISION, Rounding::Up)? - 1;
+                self.droplet_supply - ending_supply.min(self.droplet_supply as u128) as u64
+            }
+            Err(error) if error == errors::ErrorCode::AmountNotEnough.into() => self.droplet_supply,
+            Err(error) => return Err(error),
+        };
+
+        let floor_amount = match holder_balance {
+            0 => self.droplet_supply,
+            _ => math::mul_div(
+                gross as u128,
+                self.droplet_supply as u128,
+                holder_balance as u128,
+                Rounding::Up,
+            )
+            .map_or(self.droplet_supply, |floor_amount| {
+                floor_amount.min(self.droplet_supply as u128) as u64
+            }),
+        };
+        Ok(curve_amount.min(floor_amount).max(1))
+    }
+
+    // The price of each token in the funding round is c/r.
+    // c is the max amount of droplets per pool - 1,000,000,000
+    // r is the amount of quote tokens to be raised
+    // A phase price is instead expressed in droplets per whole quote token. Mints round down.
+    pub fn calculate_quote_to_droplets(
+        &self,
+        quote_amount: u64,
+        phase: &RaisePhase,
+    ) -> Result<u64> {
+        let (droplets_per_unit, quote_units) = if phase.droplets_per_quote_token != 0 {
+            let quote_unit = 10_u128
+                .checked_pow(self.quote_decimals as u32)
+                .ok_or(errors::ErrorCode::InvalidCalculation)?;
+            (phase.droplets_per_quote_token as u128, quote_unit)
+        } else {
+            (1_000_000_000, self.raise_goal as u128)
+        };
+
+        let droplets = math::mul_div(
+            quote_amount as u128,
+            droplets_per_unit * DROPLET_MINT_DECIMALS as u128,
+            quote_units,
+            Rounding::Down,
+        )?;
+        math::to_u64(droplets)
+    }
+}
+
+// run a test to see if the price is correct
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_pricing() {
+        let pool = Pool {
+            owner: Pubkey::default(),
+            uri: String::from(""),
+            name: String::from("Test Pool"),
+            symbol: String::from("TEST"),
+            droplet_mint: Pubkey::default(),
+            quote_mint: NATIVE_SOL_SPL_MINT,
+            quote_decimals: 9,
+            droplet_supply: 0,
+            droplet_liquidity: 0,
+            owner_liquidity: 0,
+            curve: CurveKind::default(),
+            owner_fees: OwnerFees::default(),
+            raise_goal: 300_000_000_000,
+            min_raise: 0,
+            total_raised: 0,
+            min_contribution: 0,
+            max_contribution_per_wallet: 0,
+            allowlist_root: [0; 32],
+            guaranteed_reserve: 0,
+            guaranteed_raised: 0,
+            phases: [RaisePhase::default(); 3],
+            raise_period_start: 0,
+            raise_period_end: 0,
+            maturity_date: 0,
+            settled_supply: 0,
+            settled_vault_balance: 0,
+            asset_count: 0,
+            status: PoolStatus::Created,
+            is_filled: false,
+            halted: false,
+            halted_by_admin: false,
+            bump: 0,
+        };
+
+        // 0.23 SOL of a 300 SOL raise mints 0.23 / 300 of the billion droplets
+        let phase = RaisePhase::default();
+        let droplets = pool
+            .calculate_quote_to_droplets(230_000_000, &phase)
+            .unwrap();
+        assert_eq!(droplets, 766_666_666_666);
+        let raise = pool
+            .calculate_quote_to_droplets(pool.raise_goal, &phase)
+            .unwrap();
+        assert_eq!(raise, MAX_DROPLET_SUPPLY);
+
+        // The first whole droplet costs k * base_price / 2 per base unit squared, and
+        // leaves the spot price at k * base_price per base unit
+        let mut pool = pool;
+        let config = ProtocolConfig::default();
+        assert_eq!(pool.get_current_price().unwrap(), 0);
+        assert_eq!(
+            pool.get_buy_price(DROPLET_MINT_DECIMALS).unwrap(),
+            500_000_000_000
+        );
+        let quote = pool
+            .quote_buy_exact_in(&config, 500_000_000_000, 0)
+            .unwrap();
+        assert_eq!(quote.droplets, DROPLET_MINT_DECIMALS);
+
+        // Selling it straight back returns the supply and the price to where they started
+        pool.droplet_supply = quote.resulting_supply;
+        assert_eq!(pool.get_current_price().unwrap(), 1_000_000_000_000);
+        assert_eq!(
+            pool.get_sell_price(DROPLET_MINT_DECIMALS).unwrap(),
+            500_000_000_000
+        );
+        let quote = pool
+            .quote_sell_exact_out(&config, 500_000_000_000, 500_000_000_000)
+            .unwrap();
+        assert_eq!(quote.droplets, DROPLET_MINT_DECIMALS);
+        assert_eq!(quote.resulting_supply, 0);
+    }
+
+    #[test]
+    fn test_pool_status_transitions() {
+        let mut pool = Pool::default();
+
+        assert!(pool.transition(PoolStatus::Active).is_err());
+        assert_eq!(
+            pool.transition(PoolStatus::Raising).unwrap(),
+            PoolStatus::Created
+        );
+        assert!(pool.ensure_status(&[PoolStatus::Raising]).is_ok());
+        assert!(pool.ensure_status(&[PoolStatus::Active]).is_err());
+
+        pool.transition(PoolStatus::Active).unwrap();
+        assert!(pool.transition(PoolStatus::Failed).is_err());
+        pool.transition(PoolStatus::Matured).unwrap();
+        pool.transition(PoolStatus::Settled).unwrap();
+        pool.transition(PoolStatus::Closed).unwrap();
+        assert!(pool.transition(PoolStatus::Raising).is_err());
+
+        // A pool whose mint was never created can still fail and close
+        let mut pool = Pool::default();
+        pool.transition(PoolStatus::Failed).unwrap();
+        assert!(pool.ensure_closable(0).is_ok());
+
+        // Droplets left in the pool's droplet vault don't block closing, any others do
+        pool.droplet_supply = 10;
+        assert!(pool.ensure_closable(9).is_err());
+        assert!(pool.ensure_closable(10).is_ok());
+        pool.transition(PoolStatus::Closed).unwrap();
+        assert!(pool.transition(PoolStatus::Failed).is_err());
+    }
+
+    #[test]
+    fn test_fee_ledger() {
+        let mut ledger = FeeLedger {
+            mint: Pubkey::default(),
+            vault: Pubkey::default(),
+            counters: FeeCounters::default(),
+            collected: TradeFees::default(),
+            bump: 0,
+        };
+        ledger.record_fee(FeeSource::Buy, 300).unwrap();
+        ledger.record_fee(FeeSource::Sell, 200).unwrap();
+        ledger.record_fee(FeeSource::Buy, 100).unwrap();
+        ledger.counters.record_withdrawal(250).unwrap();
+        assert_eq!((ledger.collected.buy, ledger.collected.sell), (400, 200));
+        assert_eq!(ledger.collected.contribute + ledger.collected.rescind, 0);
+        assert_eq!(ledger.counters.total_collected, 600);
+        assert_eq!(ledger.counters.total_withdrawn, 250);
+
+        ledger.collected.rescind = u64::MAX;
+        assert!(ledger.record_fee(FeeSource::Rescind, 1).is_err());
+    }
+
+    #[test]
+    fn test_member_ledger() {
+        let mut member = Member::default();
+        member
+            .record_contribution(RaisePhaseKind::Guaranteed, 1_000, 1_000_000, 100)
+            .unwrap();
+        member
+            .record_contribution(RaisePhaseKind::Public, 500, 700_001, 200)
+            .unwrap();
+        assert_eq!(member.first_contribution_at, 100);
+        assert_eq!(member.last_contribution_at, 200);
+        assert_eq!(member.contributed, 1_500);
+        assert_eq!(member.droplets_minted, 1_700_001);
+        assert_eq!(member.phase_contributed, [1_000, 0, 500]);
+
+        // Rescinds unwind every minted droplet, whatever the split
+        let mut burned = 0;
+        for amount in [333, 667, 500] {
+            burned += member.droplets_for_rescind(amount).unwrap();
+            member.record_rescind(amount).unwrap();
+        }
+        assert_eq!(burned, member.droplets_minted);
+        assert_eq!(member.net_contribution(), 0);
+        assert!(member.droplets_for_rescind(1).is_err());
+        assert!(member.record_rescind(1).is_err());
+
+        // Guaranteed phases only take what's left of the guarantee
+        let (guaranteed, fcfs, public) = (
+            RaisePhaseKind::Guaranteed,
+            RaisePhaseKind::AllowlistFcfs,
+            RaisePhaseKind::Public,
+        );
+        let mut member = Member::default();
+        assert_eq!(
+            member
+                .claim_allocation(guaranteed, 600, 1_000, true)
+                .unwrap(),
+            600
+        );
+        assert!(member
+            .claim_allocation(guaranteed, 401, 1_000, true)
+            .is_err());
+        assert_eq!(member.guarantee_left(1_000), 400);
+
+        // Later phases claim the rest of it first, then take FCFS capacity
+        assert_eq!(
+            member.claim_allocation(fcfs, 700, 1_000, true).unwrap(),
+            400
+        );
+        assert_eq!(
+            (member.guaranteed_claimed, member.fcfs_claimed),
+            (1_000, 300)
+        );
+        assert_eq!(member.claim_allocation(public, 50, 1_000, true).unwrap(), 0);
+        assert_eq!(member.fcfs_claimed, 300);
+
+        // Uncapped leaves carry no guarantee
+        let mut member = Member::default();
+        assert!(member.claim_allocation(guaranteed, 1, 0, true).is_err());
+
+        // Without a guaranteed phase the allocation caps FCFS claims, across rescinds
+        let mut member = Member::default();
+        assert_eq!(member.claim_allocation(fcfs, 600, 1_000, false).unwrap(), 0);
+        member.claim_allocation(fcfs, 400, 1_000, false).unwrap();
+        assert!(member.claim_allocation(fcfs, 1, 1_000, false).is_err());
+        assert_eq!((member.guaranteed_claimed, member.fcfs_claimed), (0, 1_000));
+        member
+            .claim_allocation(fcfs, u64::MAX - 1_000, 0, false)
+            .unwrap();
+        assert!(member.claim_allocation(fcfs, 1, 0, false).is_err());
+    }
+
+    #[test]
+    fn test_guaranteed_reserve() {
+        let config = ProtocolConfig::default();
+        let mut pool = Pool {
+            raise_goal: 1_000,
+            raise_period_start: 100,
+            raise_period_end: 1_000,
+            ..Default::default()
+        };
+        pool.phase_mut(RaisePhaseKind::Public).start = 100;
+
+        // A reserve opens a guaranteed phase, and can't exceed the raise or go without one
+        assert!(pool.set_allowlist([7; 32], 500, 1_001).is_err());
+        assert!(pool.set_allowlist([0; 32], 0, 600).is_err());
+        pool.set_allowlist([7; 32], 500, 600).unwrap();
+        assert!(pool.has_guarantees());
+        assert_eq!(
+            pool.active_phase(100).unwrap().0,
+            RaisePhaseKind::Guaranteed
+        );
+        let public = *pool.phase(RaisePhaseKind::Public);
+
+        // FCFS and public contributions only reach what isn't reserved
+        assert_eq!(pool.contribution_capacity(0), 400);
+        let quote = pool.quote_contribute(&config, 1_000, &public, 0).unwrap();
+        assert_eq!(quote.value, 400);
+
+        // Guarantee holders reach past it, up to what's left of their guarantee
+        assert_eq!(pool.contribution_capacity(250), 650);
+        assert_eq!(pool.contribution_capacity(10_000), 1_000);
+        pool.record_guaranteed(250).unwrap();
+        pool.total_raised = 250;
+        assert_eq!(pool.unclaimed_guarantees(), 350);
+        assert_eq!(pool.contribution_capacity(0), 400);
+
+        // Once the open part is raised, only the reserve is left
+        pool.total_raised = 650;
+        assert_eq!(pool.contribution_capacity(0), 0);
+        assert_eq!(
+            pool.quote_contribute(&config, 1, &public, 0).unwrap_err(),
+            errors::ErrorCode::RaiseReservedForGuarantees.into()
+        );
+        assert_eq!(pool.contribution_capacity(100), 100);
+
+        pool.total_raised = 1_000;
+        assert_eq!(
+            pool.quote_contribute(&config, 1, &public, 100).unwrap_err(),
+            errors::ErrorCode::PoolFilled.into()
+        );
+    }
+
+    #[test]
+    fn test_contribution_limits() {
+        let pool = Pool {
+            min_contribution: 100,
+            max_contribution_per_wallet: 1_000,
+            ..Default::default()
+        };
+        let phase = RaisePhase::default();
+        assert!(pool
+            .ensure_contribution_limits(&phase, 100, 100, 0, 0)
+            .is_ok());
+        assert!(pool
+            .ensure_contribution_limits(&phase, 99, 99, 0, 0)
+            .is_err());
+        assert!(pool
+            .ensure_contribution_limits(&phase, 500, 500, 500, 0)
+            .is_ok());
+        assert!(pool
+            .ensure_contribution_limits(&phase, 500, 500, 501, 0)
+            .is_err());
+
+        // Phase caps only count what went into the phase, the pool cap everything
+        let capped_phase = RaisePhase {
+            wallet_cap: 600,
+            ..Default::default()
+        };
+        assert!(pool
+            .ensure_contribution_limits(&capped_phase, 500, 500, 400, 100)
+            .is_ok());
+        assert!(pool
+            .ensure_contribution_limits(&capped_phase, 500, 500, 400, 101)
+            .is_err());
+        assert!(pool
+            .ensure_contribution_limits(&capped_phase, 500, 500, 501, 0)
+            .is_err());
+
+        let unlimited = Pool::default();
+        assert!(unlimited
+            .ensure_contribution_limits(&phase, 1, 1, u64::MAX - 1, u64::MAX - 1)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_raise_phases() {
+        let mut pool = Pool::default();
+        assert!(pool.active_phase(i64::MAX).is_none());
+
+        *pool.phase_mut(RaisePhaseKind::Guaranteed) = RaisePhase {
+            start: 100,
+            wallet_cap: 0,
+            droplets_per_quote_token: 4_000,
+        };
+        pool.phase_mut(RaisePhaseKind::Public).start = 200;
+        assert!(RaisePhase::validate_schedule(&pool.phases, 100, 300).is_ok());
+        assert!(RaisePhase::validate_schedule(&pool.phases, 50, 300).is_err());
+        assert!(RaisePhase::validate_schedule(&pool.phases, 100, 150).is_err());
+
+        assert!(pool.active_phase(99).is_none());
+        assert_eq!(
+            pool.active_phase(100).unwrap().0,
+            RaisePhaseKind::Guaranteed
+        );
+        assert_eq!(
+            pool.active_phase(199).unwrap().0,
+            RaisePhaseKind::Guaranteed
+        );
+        assert_eq!(pool.active_phase(200).unwrap().0, RaisePhaseKind::Public);
+
+        // Phases can't run out of order or all be skipped
+        pool.phase_mut(RaisePhaseKind::AllowlistFcfs).start = 250;
+        assert!(RaisePhase::validate_schedule(&pool.phases, 100, 300).is_err());
+        assert!(RaisePhase::validate_schedule(&[RaisePhase::default(); 3], 100, 300).is_err());
+
+        // Phase prices override the raise goal price
+        pool.quote_decimals = 9;
+        pool.raise_goal = 1_000 * LAMPORTS_PER_SOL;
+        let guaranteed = *pool.phase(RaisePhaseKind::Guaranteed);
+        let public = *pool.phase(RaisePhaseKind::Public);
+        assert_eq!(
+            pool.calculate_quote_to_droplets(LAMPORTS_PER_SOL, &guaranteed)
+                .unwrap(),
+            4_000 * DROPLET_MINT_DECIMALS
+        );
+        assert_eq!(
+            pool.calculate_quote_to_droplets(LAMPORTS_PER_SOL, &public)
+                .unwrap(),
+            1_000_000 * DROPLET_MINT_DECIMALS
+        );
+    }
+
+    #[test]
+    fn test_set_allowlist() {
+        // Pools created without phases start with a single public phase
+        let mut pool = Pool {
+            raise_period_start: 100,
+            raise_period_end: 1_000,
+            ..Default::default()
+        };
+        pool.phase_mut(RaisePhaseKind::Public).start = 100;
+        let root = [7; 32];
+
+        // Setting a root gates the raise until the public phase opens
+        assert_eq!(pool.set_allowlist(root, 500, 0).unwrap(), 500);
+        assert_eq!(pool.allowlist_root, root);
+        assert_eq!(
+            pool.active_phase(100).unwrap().0,
+            RaisePhaseKind::AllowlistFcfs
+        );
+        assert_eq!(pool.active_phase(500).unwrap().0, RaisePhaseKind::Public);
+
+        // Rotating it can keep the raise allowlisted to the end, or reopen it early
+        let rotated = [8; 32];
+        assert_eq!(pool.set_allowlist(rotated, 0, 0).unwrap(), 0);
+        assert_eq!(
+            pool.active_phase(1_000).unwrap().0,
+            RaisePhaseKind::AllowlistFcfs
+        );
+        assert_eq!(pool.set_allowlist(rotated, 50, 0).unwrap(), 100);
+        assert_eq!(pool.active_phase(100).unwrap().0, RaisePhaseKind::Public);
+
+        // A public phase past the raise is rejected and leaves the pool untouched
+        assert!(pool.set_allowlist(root, 1_001, 0).is_err());
+        assert_eq!(pool.allowlist_root, rotated);
+
+        // A zeroed root removes the allowlist phases and opens the whole raise
+        assert_eq!(pool.set_allowlist([0; 32], 500, 0).unwrap(), 100);
+        assert_eq!(pool.allowlist_root, [0; 32]);
+        assert_eq!(pool.phase(RaisePhaseKind::AllowlistFcfs).start, 0);
+        assert_eq!(pool.active_phase(100).unwrap().0, RaisePhaseKind::Public);
+
+        // Configured allowlist phases keep their schedule
+        *pool.phase_mut(RaisePhaseKind::Guaranteed) = RaisePhase {
+            start: 100,
+            wallet_cap: 1_000,
+            droplets_per_quote_token: 0,
+        };
+        pool.set_allowlist(root, 300, 0).unwrap();
+        assert_eq!(pool.phase(RaisePhaseKind::Guaranteed).wallet_cap, 1_000);
+        assert_eq!(pool.phase(RaisePhaseKind::AllowlistFcfs).start, 0);
+        assert_eq!(
+            pool.active_phase(299).unwrap().0,
+            RaisePhaseKind::Guaranteed
+        );
+    }
+
+    #[test]
+    fn test_quote_decimals() {
+        // The same raise in SOL and in a 6 decimal stablecoin mints the same droplets
+        let sol_pool = Pool {
+            quote_decimals: 9,
+            raise_goal: 1_000 * LAMPORTS_PER_SOL,
+            ..Default::default()
+        };
+        let usdc_pool = Pool {
+            quote_decimals: 6,
+            raise_goal: 1_000 * 1_000_000,
+            ..Default::default()
+        };
+        let phase = RaisePhase::default();
+
+        let sol_droplets = sol_pool
+            .calculate_quote_to_droplets(LAMPORTS_PER_SOL, &phase)
+            .unwrap();
+        let usdc_droplets = usdc_pool
+            .calculate_quote_to_droplets(1_000_000, &phase)
+            .unwrap();
+        assert_eq!(sol_droplets, 1_000_000 * DROPLET_MINT_DECIMALS);
+        assert_eq!(usdc_droplets, sol_droplets);
+    }
+
+    #[test]
+    fn test_curve_rounding() {
+        let mut pool = Pool {
+            droplet_supply: 3,
+            ..Default::default()
+        };
+
+        // k * base = 1, so buying 1 from 3 costs (16 - 9) / 2 = 3.5
+        assert_eq!(pool.get_buy_price(1).unwrap(), 4);
+        pool.droplet_supply = 4;
+        assert_eq!(pool.get_sell_price(1).unwrap(), 3);
+        assert_eq!(pool.get_current_price().unwrap(), 4 * DROPLET_MINT_DECIMALS);
+
+        // A round trip never pays out more than it took in
+        for supply in [0u64, 1, 7, 999, 123_456_789] {
+            for amount in [1u64, 2, 3, 10, 77_777] {
+                pool.droplet_supply = supply;
+                let cost = pool.get_buy_price(amount).unwrap();
+                pool.droplet_supply = supply + amount;
+                let proceeds = pool.get_sell_price(amount).unwrap();
+                assert!(proceeds <= cost);
+                assert!(cost - proceeds <= 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_curve_large_supply() {
+        // Past the old u64::pow(2) overflow at ~4.29e9 base units
+        let mut pool = Pool {
+            droplet_supply: 10_000_000_000,
+            ..Default::default()
+        };
+        let cost = pool.get_buy_price(1_000_000).unwrap();
+        assert_eq!(cost, 1_000_000 * 10_000_500_000);
+        pool.droplet_supply += 1_000_000;
+        assert_eq!(pool.get_sell_price(1_000_000).unwrap(), cost);
+
+        // Prices and costs that don't fit in a u64 fail rather than wrap
+        pool.droplet_supply = MAX_DROPLET_SUPPLY;
+        assert!(pool.get_current_price().is_err());
+        assert!(pool.get_buy_price(MAX_DROPLET_SUPPLY).is_err());
+        assert!(pool.get_sell_price(MAX_DROPLET_SUPPLY).is_err());
+        pool.droplet_supply = u64::MAX;
+        assert!(pool.get_buy_price(1).is_err());
+    }
+
+    #[test]
+    fn test_pro_rata_share_empties_vault() {
+        let mut pool = Pool {
+            droplet_supply: 3,
+            ..Default::default()
+        };
+        let mut vault_balance = 100;
+
+        for _ in 0..3 {
+            let share = pool.pro_rata_share(vault_balance, 1).unwrap();
+            vault_balance -= share;
+            pool.droplet_supply -= 1;
+        }
+
+        assert_eq!(vault_balance, 0);
+        assert!(pool.pro_rata_share(100, 0).is_err());
+    }
+
+    #[test]
+    fn test_deadline() {
+        assert!(Pool::ensure_before_deadline(None, i64::MAX).is_ok());
+        assert!(Pool::ensure_before_deadline(Some(100), 99).is_ok());
+        assert!(Pool::ensure_before_deadline(Some(100), 100).is_ok());
+        assert!(Pool::ensure_before_deadline(Some(100), 101).is_err());
+    }
+
+    #[test]
+    fn test_exact_trades() {
+        let config = ProtocolConfig {
+            program_fee_bps: 200,
+            ..Default::default()
+        };
+        let mut pool = Pool {
+            droplet_supply: 25 * DROPLET_MINT_DECIMALS,
+            owner_fees: OwnerFees {
+                buy_bps: 300,
+                sell_bps: 300,
+                contribute_bps: 0,
+            },
+            ..Default::default()
+        };
+
+        for budget in [30_000_000, 123_456_789, LAMPORTS_PER_SOL] {
+            let quote = pool.quote_buy_exact_in(&config, budget, 0).unwrap();
+            assert!(quote.settlement <= budget);
+            assert_eq!(quote, pool.quote_buy(&config, quote.droplets, 0).unwrap());
+            let next = pool.quote_buy(&config, quote.droplets + 1, 0).unwrap();
+            assert!(next.settlement > budget);
+        }
+        // Too little for a single droplet base unit
+        let one = pool.quote_buy(&config, 1, 0).unwrap();
+        assert!(pool
+            .quote_buy_exact_in(&config, one.settlement - 1, 0)
+            .is_err());
+
+        // A vault holding exactly the curve integral backs every sell
+        let vault = pool.get_sell_price(pool.droplet_supply).unwrap();
+        for target in [1, 1_000, 50_000_000] {
+            let quote = pool.quote_sell_exact_out(&config, target, vault).unwrap();
+            assert!(quote.settlement >= target);
+            if quote.droplets > 1 {
+                let previous = pool.quote_sell(&config, quote.droplets - 1, vault).unwrap();
+                assert!(previous.settlement < target);
+            }
+        }
+
+        // Proceeds can't exceed what selling the whole supply pays
+        let all = pool
+            .quote_sell(&config, pool.droplet_supply, vault)
+            .unwrap();
+        assert!(pool
+            .quote_sell_exact_out(&config, all.settlement + 1, vault)
+            .is_err());
+
+        // Sells the vault can't cover surface as insolvent rather than short
+        let short_vault = vault / 2;
+        assert_eq!(
+            pool.quote_sell_exact_out(&config, all.settlement, short_vault)
+                .unwrap_err(),
+            errors::ErrorCode::PoolInsolvent.into()
+        );
+
+        pool.droplet_supply = 0;
+        assert!(pool.quote_sell_exact_out(&config, 1, vault).is_err());
+    }
+
+    #[test]
+    fn test_exact_trades_settle_quickly() {
+        let config = ProtocolConfig {
+            program_fee_bps: 200,
+            ..Default::default()
+        };
+        let inclusive = ProtocolConfig {
+            fee_mode: FeeMode::Inclusive,
+            ..config.clone()
+        };
+        let curves = [
+            CurveKind::default(),
+            CurveKind::Exponential {
+                initial_price: 1_000,
+                growth_rate: 10_000_000,
+                step: 1_000_000,
+            },
+            CurveKind::ConstantProduct {
+                virtual_quote_reserve: 30_000_000_000,
+                virtual_droplet_reserve: 1_073_000_000_000_000,
+            },
+            CurveKind::Capped {
+                k: K,
+                base_price: BASE_DROPLET_PRICE,
+                max_price: 5_000_000_000,
+            },
+        ];
+
+        // Bisecting the whole supply range would price around 50 trades
+        for curve in curves {
+            for (supply, liquidity, holder_balance) in [
+                (0, 0, 0),
+                (1, 0, 0),
+                (25 * DROPLET_MINT_DECIMALS, 0, 0),
+                (25 * DROPLET_MINT_DECIMALS, 40 * LAMPORTS_PER_SOL, 0),
+                (10 * DROPLET_MINT_DECIMALS, 0, 200_000 * LAMPORTS_PER_SOL),
+            ] {
+                let pool = Pool {
+                    quote_decimals: 9,
+                    droplet_supply: supply,
+                    droplet_liquidity: liquidity,
+                    owner_liquidity: liquidity,
+                    curve,
+                    owner_fees: OwnerFees {
+                        buy_bps: 300,
+                        sell_bps: 300,
+                        contribute_bps: 0,
+                    },
+                    ..Default::default()
+                };
+                let vault = liquidity + holder_balance;
+                for config in [&config, &inclusive] {
+                    for budget in [
+                        1,
+                        30_000_000,
+                        123_456_789,
+                        1_000 * LAMPORTS_PER_SOL,
+                        u64::MAX,
+                    ] {
+                        let Ok((quote, priced)) = pool.settle_buy_exact_in(config, budget, vault)
+                        else {
+                            let one = pool.quote_buy(config, 1, vault);
+                            assert!(one.map_or(true, |one| one.settlement > budget));
+                            continue;
+                        };
+                        assert!(priced <= 2, "{} priced for {:?} {}", priced, curve, budget);
+                        assert!(quote.settlement <= budget);
+                        if quote.resulting_supply < MAX_DROPLET_SUPPLY {
+                            let next = pool.quote_buy(config, quote.droplets + 1, vault);
+                            assert!(next.map_or(true, |next| next.settlement > budget));
+                        }
+                    }
+                }
+
+                // Sells against the curve integral, or the NAV floor when it's higher
+                let vault = match holder_balance {
+                    0 => liquidity + pool.get_sell_price(supply).unwrap_or(0),
+                    _ => vault,
+                };
+                for target in [1, 1_000, 30_000_000, LAMPORTS_PER_SOL] {
+                    let Ok((quote, priced)) = pool.settle_sell_exact_out(&config, target, vault)
+                    else {
+                        let all = pool.quote_sell(&config, supply, vault);
+                        assert!(all.map_or(true, |all| all.settlement < target));
+                        continue;
+                    };
+                    assert!(priced <= 2, "{} priced for {:?} {}", priced, curve, target);
+                    assert!(quote.settlement >= target);
+                    if quote.droplets > 1 {
+                        let previous = pool.quote_sell(&config, quote.droplets - 1, vault).unwrap();
+                        assert!(previous.settlement < target);
+                    }
+                }
+            }
+        }
+
+        // Narrow ranges at the supply cap still settle
+        let near_cap = Pool {
+            droplet_supply: MAX_DROPLET_SUPPLY - 3,
+            curve: CurveKind::Linear {
+                k: 1,
+                base_price: 1,
+            },
+            ..Default::default()
+        };
+        let quote = near_cap.quote_buy_exact_in(&config, u64::MAX, 0).unwrap();
+        assert_eq!(quote.droplets, 3);
+        let full = near_cap.quote_buy(&config, 2, 0).unwrap();
+        let quote = near_cap
+            .quote_buy_exact_in(&config, full.settlement, 0)
+            .unwrap();
+        assert_eq!(quote.droplets, 2);
+    }
+
+    #[test]
+    fn test_contribute_quote() {
+        let config = ProtocolConfig {
+            program_fee_bps: 200,
+            ..Default::default()
+        };
+        let pool = Pool {
+            raise_goal: LAMPORTS_PER_SOL,
+            total_raised: LAMPORTS_PER_SOL / 4,
+            droplet_supply: 250_000_000 * DROPLET_MINT_DECIMALS,
+            ..Default::default()
+        };
+        let phase = RaisePhase::default();
+
+        let quote = pool
+            .quote_contribute(&config, LAMPORTS_PER_SOL / 2, &phase, 0)
+            .unwrap();
+        assert_eq!(quote.value, LAMPORTS_PER_SOL / 2);
+        assert_eq!(quote.droplets, 500_000_000 * DROPLET_MINT_DECIMALS);
+        assert_eq!(quote.program_fee, LAMPORTS_PER_SOL / 100);
+        assert_eq!(quote.settlement, quote.value + quote.program_fee);
+        assert_eq!(quote.resulting_supply, 750_000_000 * DROPLET_MINT_DECIMALS);
+
+        // Clamped at the raise goal, fees only on the accepted amount
+        let quote = pool
+            .quote_contribute(&config, LAMPORTS_PER_SOL, &phase, 0)
+            .unwrap();
+        assert_eq!(quote.value, 3 * LAMPORTS_PER_SOL / 4);
+        assert_eq!(
+            quote.resulting_supply,
+            1_000_000_000 * DROPLET_MINT_DECIMALS
+        );
+
+        let filled = Pool {
+            total_raised: LAMPORTS_PER_SOL,
+            ..pool.clone()
+        };
+        assert!(filled.quote_contribute(&config, 1, &phase, 0).is_err());
+
+        let trading = Pool {
+            droplet_supply: 250 * DROPLET_MINT_DECIMALS,
+            ..pool.clone()
+        };
+        let buy = trading
+            .quote_buy(&config, DROPLET_MINT_DECIMALS, 0)
+            .unwrap();
+        assert_eq!(buy.resulting_supply, 251 * DROPLET_MINT_DECIMALS);
+        let sell = trading
+            .quote_sell(&config, DROPLET_MINT_DECIMALS, u64::MAX)
+            .unwrap();
+        assert_eq!(sell.resulting_supply, 249 * DROPLET_MINT_DECIMALS);
+        assert!(trading
+            .quote_sell(&config, trading.droplet_supply + 1, u64::MAX)
+            .is_err());
+    }
+
+    #[test]
+    fn test_liquidity_pricing() {
+        let curves = [
+            CurveKind::default(),
+            CurveKind::Exponential {
+                initial_price: 1_000,
+                growth_rate: 1_000_000,
+                step: DROPLET_MINT_DECIMALS,
+            },
+            CurveKind::ConstantProduct {
+                virtual_quote_reserve: 30 * LAMPORTS_PER_SOL,
+                virtual_droplet_reserve: 1_000_000 * DROPLET_MINT_DECIMALS,
+            },
+        ];
+
+        for curve in curves {
+            let mut pool = Pool {
+                droplet_supply: 5_000 * DROPLET_MINT_DECIMALS,
+                quote_decimals: 9,
+                curve,
+                ..Default::default()
+            };
+
+            // Without liquidity the pool prices exactly on its curve
+            assert_eq!(pool.get_liquidity_factor().unwrap(), K_PRECISION);
+            for amount in [1, 777, 42 * DROPLET_MINT_DECIMALS] {
+                assert_eq!(
+                    pool.get_buy_price(amount).unwrap(),
+                    curve.buy_cost(pool.droplet_supply, amount).unwrap()
+                );
+                assert_eq!(
+                    pool.get_sell_price(amount).unwrap(),
+                    curve.sell_proceeds(pool.droplet_supply, amount).unwrap()
+                );
+            }
+            assert_eq!(
+                pool.get_current_price().unwrap(),
+                curve.spot_price(pool.droplet_supply).unwrap()
+            );
+
+            let amount = 100 * DROPLET_MINT_DECIMALS;
+            let shallow_buy = pool.get_buy_price(amount).unwrap();
+            let shallow_impact =
+                shallow_buy - pool.get_current_price().unwrap() * (amount / DROPLET_MINT_DECIMALS);
+
+            // 100 whole quote tokens doubles the depth
+            pool.record_liquidity(100 * LAMPORTS_PER_SOL).unwrap();
+            assert_eq!(pool.get_liquidity_factor().unwrap(), 2 * K_PRECISION);
+
+            let deep_buy = pool.get_buy_price(amount).unwrap();
+            let deep_impact =
+                deep_buy - pool.get_current_price().unwrap() * (amount / DROPLET_MINT_DECIMALS);
+            assert!(deep_impact < shallow_impact);
+
+            // Round trips through the scaled curve never pay out more than they cost
+            let buy = pool.get_buy_price(amount).unwrap();
+            pool.droplet_supply += amount;
+            assert!(pool.get_sell_price(amount).unwrap() <= buy);
+        }
+    }
+
+    #[test]
+    fn test_owner_liquidity() {
+        let mut pool = Pool {
+            droplet_supply: 100 * DROPLET_MINT_DECIMALS,
+            status: PoolStatus::Failed,
+            ..Default::default()
+        };
+        pool.record_owner_liquidity(1_000).unwrap();
+        pool.record_liquidity(500).unwrap();
+        assert_eq!(
+            (pool.droplet_liquidity, pool.owner_liquidity),
+            (1_500, 1_000)
+        );
+
+        // Refunds and redemptions split only what the holders put in
+        let vault = 1_500;
+        assert_eq!(pool.holder_balance(vault), 500);
+        let amount = pool.droplet_supply;
+        assert_eq!(
+            pool.pro_rata_share(pool.holder_balance(vault), amount)
+                .unwrap(),
+            500
+        );
+        assert_eq!(pool.holder_balance(600), 0);
+
+        // The sell floor leaves the owner's deposit out too
+        let amount = 10 * DROPLET_MINT_DECIMALS;
+        let floor = pool
+            .pro_rata_share(pool.holder_balance(u64::MAX), amount)
+            .unwrap();
+        assert_eq!(
+            pool.get_sell_value(amount, u64::MAX).unwrap(),
+            floor.max(pool.get_sell_price(amount).unwrap())
+        );
+
+        // Failed pools hand it back at once, capped by the vault
+        assert_eq!(pool.reclaimable_liquidity(vault).unwrap(), 1_000);
+        assert_eq!(pool.reclaimable_liquidity(400).unwrap(), 400);
+        assert!(pool.reclaimable_liquidity(0).is_err());
+
+        // Settled pools only once every droplet is redeemed
+        pool.status = PoolStatus::Settled;
+        assert!(pool.reclaimable_liquidity(vault).is_err());
+        pool.droplet_supply = 0;
+        assert_eq!(pool.reclaimable_liquidity(vault).unwrap(), 1_000);
+
+        pool.status = PoolStatus::Active;
+        assert!(pool.reclaimable_liquidity(vault).is_err());
+    }
+
+    #[test]
+    fn test_sells_leave_owner_liquidity() {
+        let config = ProtocolConfig::default();
+        let mut pool = Pool {
+            droplet_supply: 100 * DROPLET_MINT_DECIMALS,
+            status: PoolStatus::Active,
+            ..Default::default()
+        };
+        pool.owner_liquidity = 10 * LAMPORTS_PER_SOL;
+        let amount = 10 * DROPLET_MINT_DECIMALS;
+
+        // Holders put in less than the curve pays for the whole supply
+        let mut vault = pool.owner_liquidity + pool.get_sell_price(4 * amount).unwrap();
+        let mut sold = 0;
+        while let Ok(quote) = pool.quote_sell(&config, amount, vault) {
+            assert!(quote.value <= pool.holder_balance(vault));
+            vault -= quote.value;
+            pool.droplet_supply -= amount;
+            sold += 1;
+        }
+        assert!(sold > 0 && pool.droplet_supply > 0);
+        assert_eq!(
+            pool.quote_sell(&config, amount, vault).unwrap_err(),
+            errors::ErrorCode::PoolInsolvent.into()
+        );
+        assert!(vault >= pool.owner_liquidity);
+
+        // The rest of the holders redeem what they left, and the owner gets the deposit back
+        pool.status = PoolStatus::Settled;
+        let redeemed = pool
+            .pro_rata_share(pool.holder_balance(vault), pool.droplet_supply)
+            .unwrap();
+        assert_eq!(redeemed, vault - pool.owner_liquidity);
+        vault -= redeemed;
+        pool.droplet_supply = 0;
+        assert_eq!(
+            pool.reclaimable_liquidity(vault).unwrap(),
+            10 * LAMPORTS_PER_SOL
+        );
+        assert_eq!(vault, pool.owner_liquidity);
+    }
+
+    #[test]
+    fn test_sell_nav_floor() {
+        let config = ProtocolConfig::default();
+        let pool = Pool {
+            droplet_supply: 100 * DROPLET_MINT_DECIMALS,
+            ..Default::default()
+        };
+        let amount = 10 * DROPLET_MINT_DECIMALS;
+        let curve_value = pool.get_sell_price(amount).unwrap();
+
+        // An under-backed vault pays the curve as long as it can cover it
+        let vault = 2 * curve_value;
+        assert_eq!(pool.get_sell_value(amount, vault).unwrap(), curve_value);
+
+        // A vault holding more than the curve implies pays the pro-rata share instead
+        let vault = 100 * curve_value;
+        assert_eq!(
+            pool.get_sell_value(amount, vault).unwrap(),
+            pool.pro_rata_share(vault, amount).unwrap()
+        );
+        assert_eq!(
+            pool.quote_sell(&config, amount, vault).unwrap().value,
+            vault / 10
+        );
+
+        // Curves that outrun the vault fail instead of draining it
+        assert!(pool.get_sell_value(amount, curve_value - 1).is_err());
+        assert!(pool.quote_sell(&config, amount, 0).is_err());
+        assert!(pool
+            .quote_sell_exact_out(&config, curve_value, curve_value - 1)
+            .is_err());
+
+        // Sells below a virtual base unit still get the floor
+        let deep = Pool {
+            droplet_liquidity: 1_000,
+            quote_decimals: 0,
+            ..pool.clone()
+        };
+        assert_eq!(deep.get_sell_price(1).unwrap(), 0);
+        assert_eq!(
+            deep.get_sell_value(1, 100 * DROPLET_MINT_DECIMALS).unwrap(),
+            1
+        );
+
+        // Buys pay the same floor, so a buy sold straight back never nets a profit
+        let vault = 100 * curve_value;
+        let buy_cost = pool.get_buy_cost(amount, vault).unwrap();
+        assert!(buy_cost > pool.get_buy_price(amount).unwrap());
+        assert_eq!(buy_cost, pool.pro_rata_share(vault, amount).unwrap());
+        let bought = Pool {
+            droplet_supply: pool.droplet_supply + amount,
+            ..pool.clone()
+        };
+        assert!(bought.get_sell_value(amount, vault + buy_cost).unwrap() <= buy_cost);
+        assert_eq!(
+            pool.quote_buy(&config, amount, vault).unwrap().value,
+            buy_cost
+        );
+        assert_eq!(
+            pool.get_buy_cost(amount, 0).unwrap(),
+            pool.get_buy_price(amount).unwrap()
+        );
+
+        // A curve too large to price is more than the vault holds
+        let overflowing = Pool {
+            droplet_supply: MAX_DROPLET_SUPPLY,
+            ..pool.clone()
+        };
+        assert!(overflowing.get_sell_price(MAX_DROPLET_SUPPLY).is_err());
+        assert_eq!(
+            overflowing
+                .get_sell_value(MAX_DROPLET_SUPPLY, u64::MAX)
+                .unwrap_err(),
+            errors::ErrorCode::PoolInsolvent.into()
+        );
+        assert_eq!(
+            overflowing.get_sell_value(0, u64::MAX).unwrap_err(),
+            errors::ErrorCode::InvalidAmount.into()
+        );
+    }
+
+    #[test]
+    fn test_owner_fees() {
+        use crate::fees::bps_fee;
+
+        let config = ProtocolConfig {
+            pool_owner_fee_bps: POOL_OWNER_FEE_BPS,
+            max_pool_owner_fee_bps: MAX_POOL_OWNER_FEE_BPS,
+            program_fee_bps: PROGRAM_FEE_BPS,
+            ..Default::default()
+        };
+
+        let defaults = OwnerFees::from_config(&config);
+        assert_eq!(defaults.buy_bps, POOL_OWNER_FEE_BPS);
+        assert_eq!(defaults.contribute_bps, 0);
+        assert!(defaults.validate(&config).is_ok());
+
+        let capped = OwnerFees {
+            buy_bps: MAX_POOL_OWNER_FEE_BPS,
+            sell_bps: 0,
+            contribute_bps: MAX_POOL_OWNER_FEE_BPS + 1,
+        };
+        assert!(capped.validate(&config).is_err());
+
+        let pool = Pool {
+            raise_goal: LAMPORTS_PER_SOL,
+            droplet_supply: 1_000 * DROPLET_MINT_DECIMALS,
+            owner_fees: OwnerFees {
+                buy_bps: 250,
+                sell_bps: 50,
+                contribute_bps: 125,
+            },
+            ..Default::default()
+        };
+
+        let buy = pool.quote_buy(&config, DROPLET_MINT_DECIMALS, 0).unwrap();
+        assert_eq!(buy.pool_owner_fee, bps_fee(buy.value, 250).unwrap());
+        assert_eq!(buy.program_fee, bps_fee(buy.value, 100).unwrap());
+
+        let sell = pool
+            .quote_sell(&config, DROPLET_MINT_DECIMALS, u64::MAX)
+            .unwrap();
+        assert_eq!(sell.pool_owner_fee, bps_fee(sell.value, 50).unwrap());
+        assert_eq!(
+            sell.settlement,
+            sell.value - sell.pool_owner_fee - sell.program_fee
+        );
+
+        // A sale worth a single base unit still prices, the fees just take all of it
+        let dust = Pool {
+            droplet_supply: 2,
+            ..pool.clone()
+        };
+        let sell = dust.quote_sell(&config, 1, 2).unwrap();
+        assert_eq!((sell.value, sell.settlement), (1, 0));
+        assert_eq!(sell.pool_owner_fee + sell.program_fee, 1);
+
+        let raising = Pool {
+            droplet_supply: 0,
+            ..pool.clone()
+        };
+        let contribution = raising
+            .quote_contribute(&config, LAMPORTS_PER_SOL, &RaisePhase::default(), 0)
+            .unwrap();
+        assert_eq!(contribution.pool_owner_fee, LAMPORTS_PER_SOL / 80);
+        assert_eq!(contribution.program_fee, LAMPORTS_PER_SOL / 100);
+        assert_eq!(
+            contribution.settlement,
+            LAMPORTS_PER_SOL + contribution.pool_owner_fee + contribution.program_fee
+        );
+    }
+
+    #[test]
+    fn test_fee_modes() {
+        let exclusive = ProtocolConfig {
+            program_fee_bps: 100,
+            ..Default::default()
+        };
+        let inclusive = ProtocolConfig {
+            fee_mode: FeeMode::Inclusive,
+            ..exclusive.clone()
+        };
+        let pool = Pool {
+            raise_goal: LAMPORTS_PER_SOL,
+            total_raised: LAMPORTS_PER_SOL / 2,
+            owner_fees: OwnerFees {
+                buy_bps: 200,
+                sell_bps: 200,
+                contribute_bps: 100,
+            },
+            ..Default::default()
+        };
+        let phase = RaisePhase::default();
+        let amount = LAMPORTS_PER_SOL / 10;
+
+        // Exclusive contributions credit the amount and charge fees on top
+        let quote = pool
+            .quote_contribute(&exclusive, amount, &phase, 0)
+            .unwrap();
+        assert_eq!(quote.value, amount);
+        assert_eq!(quote.settlement, amount + amount / 50);
+
+        // Inclusive ones charge the amount and credit what's left after fees
+        let quote = pool
+            .quote_contribute(&inclusive, amount, &phase, 0)
+            .unwrap();
+        assert_eq!(quote.settlement, amount);
+        assert_eq!(quote.value, amount - amount / 50);
+
+        // Either way only the remaining raise is credited
+        let quote = pool
+            .quote_contribute(&exclusive, LAMPORTS_PER_SOL, &phase, 0)
+            .unwrap();
+        assert_eq!(quote.value, LAMPORTS_PER_SOL / 2);
+        let quote = pool
+            .quote_contribute(&inclusive, LAMPORTS_PER_SOL, &phase, 0)
+            .unwrap();
+        assert!(quote.value >= LAMPORTS_PER_SOL / 2);
+        assert!(quote.settlement < LAMPORTS_PER_SOL);
+        assert_eq!(
+            quote.settlement,
+            quote.value + quote.pool_owner_fee + quote.program_fee
+        );
+        assert!(pool.quote_contribute(&inclusive, 1, &phase, 0).is_err());
+
+        let trading = Pool {
+            droplet_supply: 1_000 * DROPLET_MINT_DECIMALS,
+            ..pool.clone()
+        };
+        let droplets = 10 * DROPLET_MINT_DECIMALS;
+        let cost = trading.get_buy_price(droplets).unwrap();
+
+        // Buys always credit at least the curve cost to the vault
+        let buy = trading.quote_buy(&exclusive, droplets, 0).unwrap();
+        assert_eq!(buy.value, cost);
+        assert_eq!(buy.settlement, cost + buy.pool_owner_fee + buy.program_fee);
+        let buy = trading.quote_buy(&inclusive, droplets, 0).unwrap();
+        assert!(buy.value >= cost);
+        assert_eq!(
+            buy.pool_owner_fee,
+            crate::fees::bps_fee(buy.settlement, 200).unwrap()
+        );
+
+        // Sells take both fees out of the proceeds in either mode
+        let vault = trading.get_sell_price(trading.droplet_supply).unwrap();
+        for config in [&exclusive, &inclusive] {
+            let sell = trading.quote_sell(config, droplets, vault).unwrap();
+            assert_eq!(sell.value, trading.get_sell_price(droplets).unwrap());
+            assert_eq!(
+                sell.value,
+                sell.settlement + sell.pool_owner_fee + sell.program_fee
+            );
+        }
+    }
+
+    #[test]
+    fn test_pool_space() {
+        let pool = Pool {
+            uri: "u".repeat(64),
+            name: "n".repeat(24),
+            symbol: "s".repeat(10),
+            curve: CurveKind::Exponential {
+                initial_price: u64::MAX,
+                growth_rate: u64::MAX,
+                step: u64::MAX,
+            },
+            ..Default::default()
+        };
+        assert_eq!(pool.try_to_vec().unwrap().len(), Pool::INIT_SPACE);
+    }
+
+    #[test]
+    fn test_admin_transfer_delay_bounds() {
+        let mut config = ProtocolConfig {
+            pool_creation_fee: POOL_CREATION_FEE,
+            pool_owner_fee_bps: POOL_OWNER_FEE_BPS,
+            max_pool_owner_fee_bps: MAX_POOL_OWNER_FEE_BPS,
+            program_fee_bps: PROGRAM_FEE_BPS,
+            rescind_fee_bps: RESCIND_FEE_BPS,
+            k: K,
+            base_droplet_price: BASE_DROPLET_PRICE,
+            min_raise_duration: MIN_RAISE_DURATION,
+            max_raise_duration: MAX_RAISE_DURATION,
+            min_pool_term: MIN_POOL_TERM,
+            max_pool_term: MAX_POOL_TERM,
+            max_raise_start_delay: MAX_RAISE_START_DELAY,
+            admin_transfer_delay: ADMIN_TRANSFER_DELAY,
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        for (delay, valid) in [
+            (0, false),
+            (MIN_ADMIN_TRANSFER_DELAY - 1, false),
+            (MIN_ADMIN_TRANSFER_DELAY, true),
+            (MAX_ADMIN_TRANSFER_DELAY, true),
+            (MAX_ADMIN_TRANSFER_DELAY + 1, false),
+        ] {
+            config.admin_transfer_delay = delay;
+            assert_eq!(config.validate().is_ok(), valid);
+        }
+    }
+
+    #[test]
+    fn test_halt_authority() {
+        let mut pool = Pool::default();
+
+        // Owner halts are the owner's to lift
+        pool.set_halted(true, false).unwrap();
+        assert!(pool.halted && !pool.halted_by_admin);
+        pool.set_halted(false, false).unwrap();
+        assert!(!pool.halted);
+
+        // Admin halts can't be lifted or downgraded by the owner
+        pool.set_halted(true, true).unwrap();
+        pool.set_halted(true, false).unwrap();
+        assert!(pool.halted_by_admin);
+        assert!(pool.set_halted(false, false).is_err());
+        assert!(pool.halted);
+
+        pool.set_halted(false, true).unwrap();
+        assert!(!pool.halted && !pool.halted_by_admin);
+
+        // Exits stay open through any halt when configured to
+        let moonpool = Moonpool {
+            admin: Pubkey::default(),
+            pools: 0,
+            pending_admin: Pubkey::default(),
+            pending_admin_eta: 0,
+            paused: false,
+        };
+        let mut config = ProtocolConfig {
+            exits_open_when_paused: true,
+            ..Default::default()
+        };
+        pool.set_halted(true, false).unwrap();
+        assert!(pool.ensure_exits_open(&moonpool, &config).is_ok());
+        assert!(pool.ensure_trading_open(&moonpool).is_err());
+        config.exits_open_when_paused = false;
+        assert!(pool.ensure_exits_open(&moonpool, &config).is_err());
+
+        // Sells only get through an owner's halt, never a pause or an admin halt
+        assert!(pool.ensure_sells_open(&moonpool).is_ok());
+        let paused = Moonpool {
+            paused: true,
+            ..moonpool
+        };
+        assert_eq!(
+            pool.ensure_sells_open(&paused).unwrap_err(),
+            errors::ErrorCode::ProtocolPaused.into()
         );
+        pool.set_halted(false, false).unwrap();
+        assert!(pool.ensure_sells_open(&paused).is_err());
+        pool.set_halted(true, true).unwrap();
+        assert!(pool.ensure_sells_open(&moonpool).is_err());
     }
 }
@@ -51,3 +51,18 @@ export const [FEE_VAULT_PDA] = anchor.web3.PublicKey.findProgramAddressSync(
   [Buffer.from("fee_vault")],
   program.programId
 );
+
+export const [CONFIG_PDA] = anchor.web3.PublicKey.findProgramAddressSync(
+  [Buffer.from("config")],
+  program.programId
+);
+
+export const [FEE_LEDGER_PDA] = anchor.web3.PublicKey.findProgramAddressSync(
+  [Buffer.from("fee_ledger"), NATIVE_MINT.toBuffer()],
+  program.programId
+);
+
+export const [FEE_LEDGER_VAULT] = anchor.web3.PublicKey.findProgramAddressSync(
+  [Buffer.from("fee_ledger_vault"), NATIVE_MINT.toBuffer()],
+  program.programId
+);
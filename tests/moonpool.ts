@@ -28,6 +28,9 @@ import {
   connection,
   TOKEN_METADATA_PROGRAM_ID,
   FEE_VAULT_PDA,
+  FEE_LEDGER_PDA,
+  FEE_LEDGER_VAULT,
+  CONFIG_PDA,
   CP_SWAP_PROGRAM,
   CONFIG_ADDRESS,
   CREATE_POOL_FEE_RECEIVE,
@@ -64,6 +67,7 @@ describe("moonpool", () => {
         payer: payer.publicKey,
         moonpool: MOONPOOL_PDA,
         feeVault: FEE_VAULT_PDA,
+        config: CONFIG_PDA,
         systemProgram: SystemProgram.programId,
       })
       .rpc()
@@ -95,8 +99,8 @@ describe("moonpool", () => {
       TOKEN_METADATA_PROGRAM_ID
     );
 
-    const [POOL_WSOL_VAULT] = PublicKey.findProgramAddressSync(
-      [Buffer.from("wsol_vault"), POOL_PDA.toBuffer()],
+    const [POOL_QUOTE_VAULT] = PublicKey.findProgramAddressSync(
+      [Buffer.from("quote_vault"), POOL_PDA.toBuffer()],
       program.programId
     );
 
@@ -112,16 +116,34 @@ describe("moonpool", () => {
       DROPLET_MINT.toBase58()
     );
     const raiseGoal = 0.5 * LAMPORTS_PER_SOL;
+    const raiseStart = Math.floor(Date.now() / 1000);
+    const raiseDuration = 24 * 60 * 60;
+    const maturityDate = raiseStart + raiseDuration + 90 * 24 * 60 * 60;
 
     const createPoolInstruction: TransactionInstruction = await program.methods
-      .createPool(POOL_NAME, symbol, new BN(raiseGoal))
+      .createPool(POOL_NAME, {
+        symbol,
+        raiseGoal: new BN(raiseGoal),
+        minRaise: new BN(0),
+        raiseStart: new BN(raiseStart),
+        raiseDuration: new BN(raiseDuration),
+        maturityDate: new BN(maturityDate),
+        minContribution: null,
+        maxContributionPerWallet: null,
+        phases: null,
+        curve: null,
+        ownerFees: null,
+      })
       .accounts({
         moonpool: MOONPOOL_PDA,
         pool: POOL_PDA,
         feeVault: FEE_VAULT_PDA,
-        poolWsolVault: POOL_WSOL_VAULT,
+        config: CONFIG_PDA,
+        poolQuoteVault: POOL_QUOTE_VAULT,
+        feeLedger: FEE_LEDGER_PDA,
+        feeLedgerVault: FEE_LEDGER_VAULT,
         payer: payer.publicKey,
-        wsolMint: NATIVE_MINT,
+        quoteMint: NATIVE_MINT,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
@@ -133,12 +155,12 @@ describe("moonpool", () => {
         .accounts({
           moonpool: MOONPOOL_PDA,
           pool: POOL_PDA,
-          poolWsolVault: POOL_WSOL_VAULT,
+          poolQuoteVault: POOL_QUOTE_VAULT,
           poolDropletVault: POOL_DROPLET_VAULT,
           dropletMint: DROPLET_MINT,
           metadata: METADATA_PDA,
           payer: payer.publicKey,
-          wsolMint: NATIVE_MINT,
+          quoteMint: NATIVE_MINT,
           tokenProgram: TOKEN_PROGRAM_ID,
           tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -173,13 +195,8 @@ describe("moonpool", () => {
       program.programId
     );
 
-    const [FEE_VAULT_PDA] = PublicKey.findProgramAddressSync(
-      [Buffer.from("fee_vault")],
-      program.programId
-    );
-
-    const [POOL_WSOL_VAULT] = PublicKey.findProgramAddressSync(
-      [Buffer.from("wsol_vault"), POOL_PDA.toBuffer()],
+    const [POOL_QUOTE_VAULT] = PublicKey.findProgramAddressSync(
+      [Buffer.from("quote_vault"), POOL_PDA.toBuffer()],
       program.programId
     );
 
@@ -188,7 +205,7 @@ describe("moonpool", () => {
       program.programId
     );
 
-    const payerWsolTokenAccount = await getOrCreateAssociatedTokenAccount(
+    const payerQuoteTokenAccount = await getOrCreateAssociatedTokenAccount(
       connection,
       payer.payer,
       NATIVE_MINT,
@@ -204,25 +221,34 @@ describe("moonpool", () => {
       false
     );
 
+    const [MEMBER_PDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), POOL_PDA.toBuffer(), payer.publicKey.toBuffer()],
+      program.programId
+    );
+
     const sol_amount_to_contribute = 0.5;
     const droplets_per_sol = 1_000_000_000 / 0.5; // 300 is the raise goal
     const droplets_to_mint = sol_amount_to_contribute * droplets_per_sol;
     console.log(Math.floor(droplets_to_mint * Math.pow(10, 6)));
 
     await program.methods
-      .contribute(new BN(sol_amount_to_contribute * LAMPORTS_PER_SOL))
+      .contribute(new BN(sol_amount_to_contribute * LAMPORTS_PER_SOL), null)
       .accounts({
         moonpool: MOONPOOL_PDA,
-        feeVault: FEE_VAULT_PDA,
+        feeLedger: FEE_LEDGER_PDA,
+        feeLedgerVault: FEE_LEDGER_VAULT,
+        config: CONFIG_PDA,
         pool: POOL_PDA,
         poolOwner: payer.publicKey,
-        poolWsolVault: POOL_WSOL_VAULT,
+        poolQuoteVault: POOL_QUOTE_VAULT,
         poolDropletVault: POOL_DROPLET_VAULT,
-        payerWsolTokenAccount: payerWsolTokenAccount.address,
+        payerQuoteTokenAccount: payerQuoteTokenAccount.address,
+        poolOwnerQuoteTokenAccount: payerQuoteTokenAccount.address,
         payerDropletTokenAccount: payerDropletTokenAccount.address,
+        member: MEMBER_PDA,
         payer: payer.publicKey,
         dropletMint: DROPLET_MINT,
-        wsolMint: NATIVE_MINT,
+        quoteMint: NATIVE_MINT,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
@@ -508,49 +534,60 @@ describe("moonpool", () => {
       program.programId
     );
 
-    const [POOL_VAULT] = PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("pool_vault"),
-        payer.publicKey.toBuffer(),
-        POOL_PDA.toBuffer(),
-      ],
+    const [DROPLET_MINT] = PublicKey.findProgramAddressSync(
+      [Buffer.from("mint"), POOL_PDA.toBuffer()],
       program.programId
     );
 
-    const [DROPLET_MINT] = PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("droplet_mint"),
-        POOL_PDA.toBuffer(),
-        Buffer.from(POOL_NAME),
-      ],
+    const [POOL_QUOTE_VAULT] = PublicKey.findProgramAddressSync(
+      [Buffer.from("quote_vault"), POOL_PDA.toBuffer()],
       program.programId
     );
 
+    const payerQuoteTokenAccount = await getAssociatedTokenAddress(
+      NATIVE_MINT,
+      payer.publicKey,
+      false
+    );
+
     const payerDropletTokenAccount = await getAssociatedTokenAddress(
       DROPLET_MINT,
       payer.publicKey,
       false
     );
 
-    const feeVaultSolBalanceOne = await connection.getBalance(FEE_VAULT_PDA);
-    console.log(feeVaultSolBalanceOne);
+    const feeLedgerVaultBalanceOne = await connection.getTokenAccountBalance(
+      FEE_LEDGER_VAULT
+    );
+    console.log(feeLedgerVaultBalanceOne);
 
     // get pool owner from pool account
     const pool = await program.account.pool.fetch(POOL_PDA);
     const poolOwner = pool.owner;
     console.log(poolOwner.toBase58());
 
+    const poolOwnerQuoteTokenAccount = await getAssociatedTokenAddress(
+      NATIVE_MINT,
+      poolOwner,
+      false
+    );
+
     await program.methods
-      .buyDroplets(new BN(10000))
+      .buyDroplets(new BN(10000), new BN(LAMPORTS_PER_SOL), null)
       .accounts({
         moonpool: MOONPOOL_PDA,
-        feeVault: FEE_VAULT_PDA,
+        feeLedger: FEE_LEDGER_PDA,
+        feeLedgerVault: FEE_LEDGER_VAULT,
+        config: CONFIG_PDA,
         pool: POOL_PDA,
-        poolVault: POOL_VAULT,
+        poolQuoteVault: POOL_QUOTE_VAULT,
         dropletMint: DROPLET_MINT,
         poolOwner: poolOwner,
+        poolOwnerQuoteTokenAccount: poolOwnerQuoteTokenAccount,
+        payerQuoteTokenAccount: payerQuoteTokenAccount,
         payerDropletTokenAccount: payerDropletTokenAccount,
         payer: payer.publicKey,
+        quoteMint: NATIVE_MINT,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
@@ -572,8 +609,10 @@ describe("moonpool", () => {
     );
     console.log(payerDropletBalance);
 
-    const feeVaultSolBalance = await connection.getBalance(FEE_VAULT_PDA);
-    console.log(feeVaultSolBalance);
+    const feeLedgerVaultBalance = await connection.getTokenAccountBalance(
+      FEE_LEDGER_VAULT
+    );
+    console.log(feeLedgerVaultBalance);
   });
 
   it("Sells droplets", async () => {
@@ -582,24 +621,22 @@ describe("moonpool", () => {
       program.programId
     );
 
-    const [POOL_VAULT] = PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("pool_vault"),
-        payer.publicKey.toBuffer(),
-        POOL_PDA.toBuffer(),
-      ],
+    const [DROPLET_MINT] = PublicKey.findProgramAddressSync(
+      [Buffer.from("mint"), POOL_PDA.toBuffer()],
       program.programId
     );
 
-    const [DROPLET_MINT] = PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("droplet_mint"),
-        POOL_PDA.toBuffer(),
-        Buffer.from(POOL_NAME),
-      ],
+    const [POOL_QUOTE_VAULT] = PublicKey.findProgramAddressSync(
+      [Buffer.from("quote_vault"), POOL_PDA.toBuffer()],
       program.programId
     );
 
+    const payerQuoteTokenAccount = await getAssociatedTokenAddress(
+      NATIVE_MINT,
+      payer.publicKey,
+      false
+    );
+
     const payerDropletTokenAccount = await getAssociatedTokenAddress(
       DROPLET_MINT,
       payer.publicKey,
@@ -611,17 +648,28 @@ describe("moonpool", () => {
     const poolOwner = pool.owner;
     console.log(poolOwner.toBase58());
 
+    const poolOwnerQuoteTokenAccount = await getAssociatedTokenAddress(
+      NATIVE_MINT,
+      poolOwner,
+      false
+    );
+
     await program.methods
-      .sellDroplets(new BN(10000))
+      .sellDroplets(new BN(10000), new BN(0), null)
       .accounts({
         moonpool: MOONPOOL_PDA,
-        feeVault: FEE_VAULT_PDA,
+        feeLedger: FEE_LEDGER_PDA,
+        feeLedgerVault: FEE_LEDGER_VAULT,
+        config: CONFIG_PDA,
         pool: POOL_PDA,
-        poolVault: POOL_VAULT,
+        poolQuoteVault: POOL_QUOTE_VAULT,
         dropletMint: DROPLET_MINT,
         poolOwner: poolOwner,
-        payerDropletTokenAccount: payerDropletTokenAccount,
+        poolOwnerQuoteTokenAccount: poolOwnerQuoteTokenAccount,
+        sellerDropletTokenAccount: payerDropletTokenAccount,
+        payerQuoteTokenAccount: payerQuoteTokenAccount,
         payer: payer.publicKey,
+        quoteMint: NATIVE_MINT,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
@@ -643,8 +691,10 @@ describe("moonpool", () => {
     );
     console.log(payerDropletBalance);
 
-    const payerSolBalance = await connection.getBalance(payer.publicKey);
-    console.log(payerSolBalance);
+    const payerQuoteBalance = await connection.getTokenAccountBalance(
+      payerQuoteTokenAccount
+    );
+    console.log(payerQuoteBalance);
 
     // memcmp all assets with the same pool
     const assets = await program.account.asset.all([